@@ -46,9 +46,28 @@ createBody.addEventListener("click", () => {
     clickBlocker.append(detailMenu, confirm);
     body.append(clickBlocker);
 });
+const presetSelect = createElement({ tag: "select" }, "presetSelect");
+fetch("/presets")
+    .then((response) => response.json())
+    .then((presets) => {
+    presets.forEach((preset) => {
+        const option = document.createElement("option");
+        option.value = preset.name;
+        option.innerText = preset.name;
+        option.title = preset.description;
+        presetSelect.append(option);
+    });
+});
+presetSelect.addEventListener("change", () => {
+    fetch("/presets", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ name: presetSelect.value }),
+    });
+});
 const help = createElement({}, "text", "help");
 help.innerText = "Pause to modify the simulation";
-settingsMenu.append(playButton, gravity, time, resetCam, createBody, help);
+settingsMenu.append(playButton, gravity, time, resetCam, createBody, presetSelect, help);
 const detailMenu = createElement({ id: "detailMenu" }, "detailMenu", "menu");
 detailMenu.append(createElement());
 const scaleMenus = (event) => {
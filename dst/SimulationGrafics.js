@@ -14,6 +14,7 @@ let followedBody = null;
 let paths = {};
 let finishedPaths = [];
 let bodies = [];
+let particles = [];
 let updateScaleFlag = true;
 let cameraCenter = { x: 0.5, y: 0.5 };
 let pressedKeys = new Set();
@@ -129,7 +130,7 @@ const getBodyInfo = (body_index) => {
         simState: bodies.at(body_index),
     };
 };
-let bodyNames = [
+const SOLARSYSTEMNAMES = [
     "Sol",
     "Mercury",
     "Venus",
@@ -141,13 +142,14 @@ let bodyNames = [
     "Uranus",
     "Neptun",
 ];
+let bodyNames = [...SOLARSYSTEMNAMES];
 const setBodyName = (name, index = -1) => {
     if (index === -1)
         bodyNames.push(name);
     else
         bodyNames[index] = name;
 };
-let bodyColors = [
+const SOLARSYSTEMCOLORS = [
     "#E2F516",
     "#786D5F",
     "#3B2F2F",
@@ -159,6 +161,7 @@ let bodyColors = [
     "#2B65EC",
     "#56A5EC",
 ];
+let bodyColors = [...SOLARSYSTEMCOLORS];
 const setBodyColor = (color, index = -1) => {
     if (index === -1)
         bodyColors.push(color);
@@ -187,6 +190,17 @@ const drawBodies = () => {
         context.stroke();
     });
 };
+const drawParticles = () => {
+    context.fillStyle = "gray";
+    for (let i = 0; i + 2 < particles.length; i += 3) {
+        const [x, y] = pointToScreenCoords({
+            x: particles[i],
+            y: particles[i + 1],
+            z: particles[i + 2],
+        });
+        context.fillRect(x, y, 1, 1);
+    }
+};
 const drawPaths = () => {
     if (followedBody != null) {
         cameraCenter = {
@@ -273,11 +287,54 @@ const startSimulation = () => {
         console.log(d);
         EventBUS.fireEvent("removeBody", { body_index: d.index });
     });
+    eventSource.addEventListener("collision", (e) => {
+        const d = JSON.parse(e.data);
+        console.log(d);
+        EventBUS.fireEvent("removeBody", { body_index: d.removed });
+    });
+    eventSource.addEventListener("bounce", (e) => {
+        const d = JSON.parse(e.data);
+        console.log(d);
+        restartPaths(d.first, d.second);
+    });
+    eventSource.addEventListener("fragmentation", (e) => {
+        const d = JSON.parse(e.data);
+        console.log(d);
+        restartPaths(d.first, d.second);
+        insertFragments(d.first, d.first_new, d.fragments - 2);
+    });
+    eventSource.addEventListener("disruption", (e) => {
+        const d = JSON.parse(e.data);
+        console.log(d);
+        restartPaths(d.index);
+        insertFragments(d.index, d.first_new, d.fragments - 1);
+    });
+    eventSource.addEventListener("reset", (e) => {
+        const d = JSON.parse(e.data);
+        console.warn(`The body of "${d.parameter}" is gone, it now refers to body 0`);
+    });
+    eventSource.addEventListener("loaded", (e) => {
+        const d = JSON.parse(e.data);
+        paths = {};
+        finishedPaths = [];
+        bodies = [];
+        particles = [];
+        const solarSystem = d.name === "solar_system";
+        bodyNames = solarSystem ? [...SOLARSYSTEMNAMES] : [];
+        bodyColors = solarSystem ? [...SOLARSYSTEMCOLORS] : [];
+        moonIndices = solarSystem ? [4] : [];
+        while (bodyNames.length < d.bodies)
+            bodyNames.push(randomName());
+        while (bodyColors.length < d.bodies)
+            bodyColors.push(randomColor());
+        EventBUS.fireEvent("resetCam", {});
+    });
     eventSource.addEventListener("simulation", (e) => {
         const d = JSON.parse(e.data);
         EventBUS.fireEvent("metadata", d.metadata);
         const oldPos = [...bodies];
         bodies = d.simstate;
+        particles = d.particles ?? [];
         bodies.forEach((b, i) => {
             if (finishedPaths.includes(i))
                 return;
@@ -303,7 +360,7 @@ const startSimulation = () => {
             paths[i].push(pos);
         });
     });
-    Loop.registerOnUpdate({ index: 0 }, updateScale, keyboardHandler, () => context.clearRect(0, 0, c.width, c.height), drawPaths, drawBodies);
+    Loop.registerOnUpdate({ index: 0 }, updateScale, keyboardHandler, () => context.clearRect(0, 0, c.width, c.height), drawPaths, drawParticles, drawBodies);
     EventBUS.registerEventListener({ eventType: "dbclickedBody" }, (event) => {
         if (event.distance < DBCLICKDISTANCE) {
             followedBody = event.body_index;
@@ -335,6 +392,31 @@ const startSimulation = () => {
         bodies = bodies.filter((_, i) => i !== e.body_index);
     });
 };
+const restartPaths = (...indices) => {
+    indices.forEach((i) => {
+        if (i in paths)
+            paths[i] = [];
+    });
+    finishedPaths = finishedPaths.filter((i) => !indices.includes(i));
+};
+const insertFragments = (parent, firstNew, count) => {
+    while (bodyNames.length < firstNew)
+        bodyNames.push(randomName());
+    while (bodyColors.length < firstNew)
+        bodyColors.push(randomColor());
+    const name = bodyNames[parent] ?? randomName();
+    const color = bodyColors[parent] ?? randomColor();
+    const fragments = [...Array(count).keys()];
+    bodyNames.splice(firstNew, 0, ...fragments.map((n) => `${name} ${n + 1}`));
+    bodyColors.splice(firstNew, 0, ...fragments.map(() => color));
+    paths = Object.entries(paths).reduce((newObj, [i, path]) => {
+        const index = Number(i);
+        newObj[index >= firstNew ? index + count : index] = path;
+        return newObj;
+    }, {});
+    finishedPaths = finishedPaths.map((i) => (i >= firstNew ? i + count : i));
+    moonIndices = moonIndices.map((i) => (i >= firstNew ? i + count : i));
+};
 const toggleMoon = (index) => {
     if (moonIndices.includes(index)) {
         moonIndices = moonIndices.filter((v) => v !== index);
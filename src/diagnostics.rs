@@ -0,0 +1,123 @@
+use std::fmt::Display;
+
+use crate::{massive_count, Body, Softening};
+use na::Vector3;
+
+///Formats a vector as a JSON array
+pub(crate) fn json_vector(v: &Vector3<f64>) -> String {
+    format!("[{}, {}, {}]", v.x, v.y, v.z)
+}
+
+///Quantities that are conserved by the exact equations of motion. Their drift shows how trustworthy a run is.
+#[derive(Clone, Copy)]
+pub struct Diagnostics {
+    pub kinetic_energy: f64,
+    pub potential_energy: f64,
+    pub momentum: Vector3<f64>,
+    pub angular_momentum: Vector3<f64>,
+    pub center_of_mass: Vector3<f64>,
+    ///Sum of the absolute momenta. Reference for the momentum drift, as the total momentum may vanish
+    momentum_scale: f64,
+    ///Sum of the absolute angular momenta. Reference for the angular momentum drift
+    angular_momentum_scale: f64,
+    ///Change of the total energy since the baseline, relative to the total energy of the baseline
+    pub energy_drift: f64,
+    ///Change of the momentum since the baseline, relative to the sum of absolute momenta of the baseline
+    pub momentum_drift: f64,
+    ///Change of the angular momentum since the baseline, relative to the sum of absolute angular momenta of
+    ///the baseline
+    pub angular_momentum_drift: f64,
+}
+
+impl Display for Diagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{\"kinetic_energy\": {}, \"potential_energy\": {}, \"total_energy\": {}, \"momentum\": {}, \"angular_momentum\": {}, \"center_of_mass\": {}, \"energy_drift\": {}, \"momentum_drift\": {}, \"angular_momentum_drift\": {}}}",
+            self.kinetic_energy,
+            self.potential_energy,
+            self.total_energy(),
+            json_vector(&self.momentum),
+            json_vector(&self.angular_momentum),
+            json_vector(&self.center_of_mass),
+            self.energy_drift,
+            self.momentum_drift,
+            self.angular_momentum_drift
+        )
+    }
+}
+
+impl Diagnostics {
+    ///Measures the conserved quantities of `bodies`. The potential energy uses the same softening as the forces.
+    ///Test particles carry no mass, so they are skipped and cost nothing.
+    pub fn measure(
+        bodies: &[Body],
+        interaction_constant: f64,
+        softening: Softening,
+        softening_length: f64,
+    ) -> Diagnostics {
+        let mut diagnostics = Diagnostics {
+            kinetic_energy: 0.0,
+            potential_energy: 0.0,
+            momentum: Vector3::zeros(),
+            angular_momentum: Vector3::zeros(),
+            center_of_mass: Vector3::zeros(),
+            momentum_scale: 0.0,
+            angular_momentum_scale: 0.0,
+            energy_drift: 0.0,
+            momentum_drift: 0.0,
+            angular_momentum_drift: 0.0,
+        };
+        let bodies = &bodies[..massive_count(bodies)];
+        let mut total_mass = 0.0;
+        for (i, body) in bodies.iter().enumerate() {
+            let momentum = body.vel * body.mass;
+            let angular_momentum = body.pos.cross(&momentum);
+            diagnostics.kinetic_energy += 0.5 * body.mass * body.vel.norm_squared();
+            diagnostics.momentum += momentum;
+            diagnostics.angular_momentum += angular_momentum;
+            diagnostics.center_of_mass += body.pos * body.mass;
+            diagnostics.momentum_scale += momentum.norm();
+            diagnostics.angular_momentum_scale += angular_momentum.norm();
+            total_mass += body.mass;
+            for other in &bodies[i + 1..] {
+                diagnostics.potential_energy += interaction_constant
+                    * body.mass
+                    * other.mass
+                    * softening.potential((other.pos - body.pos).norm(), softening_length);
+            }
+        }
+        if total_mass > 0.0 {
+            diagnostics.center_of_mass /= total_mass;
+        }
+        diagnostics
+    }
+
+    pub fn total_energy(&self) -> f64 {
+        self.kinetic_energy + self.potential_energy
+    }
+
+    ///Fills in the drifts of the conserved quantities since `baseline`
+    pub fn compare(mut self, baseline: &Diagnostics) -> Diagnostics {
+        let relative = |change: f64, scale: f64| {
+            if scale > 0.0 {
+                change / scale
+            } else {
+                0.0
+            }
+        };
+        self.energy_drift = relative(
+            self.total_energy() - baseline.total_energy(),
+            baseline.total_energy().abs(),
+        );
+        self.momentum_drift = relative(
+            (self.momentum - baseline.momentum).norm(),
+            baseline.momentum_scale,
+        );
+        self.angular_momentum_drift = relative(
+            (self.angular_momentum - baseline.angular_momentum).norm(),
+            baseline.angular_momentum_scale,
+        );
+        self
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{massive_count, SimEvent, SimState};
+use na::Vector3;
+
+///Closest approach of a pair of bodies seen so far, while they are within the encounter distance
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Approach {
+    time: f64,
+    distance: f64,
+    speed: f64,
+    ///The pair already moves apart again and was reported
+    reported: bool,
+}
+
+impl SimState {
+    ///Looks for close encounters between massive bodies and for bodies escaping the system during the last step
+    ///of `dt` simulated seconds. `before` holds the positions and velocities of the bodies at the start of the
+    ///step. Both checks are off while their distance in the metadata is 0.
+    ///
+    ///Inside a step the bodies are assumed to move on straight lines, so the closest approach is not missed by
+    ///large steps. An encounter is reported once, when the pair moves apart again.
+    pub(crate) fn detect_encounters(
+        &mut self,
+        before: &[(Vector3<f64>, Vector3<f64>)],
+        dt: f64,
+        event_sender: crossbeam_channel::Sender<SimEvent>,
+    ) {
+        let start = self.metadata.time - dt;
+        let threshold = self.metadata.encounter_distance;
+        if threshold > 0.0 {
+            let n = massive_count(&self.bodies);
+            for first in 0..n {
+                for second in first + 1..n {
+                    let (pos0, vel0) = (
+                        before[second].0 - before[first].0,
+                        before[second].1 - before[first].1,
+                    );
+                    let pos1 = self.bodies[second].pos - self.bodies[first].pos;
+                    let vel1 = self.bodies[second].vel - self.bodies[first].vel;
+                    let path = pos1 - pos0;
+                    let s = if path.norm_squared() > 0.0 {
+                        (-pos0.dot(&path) / path.norm_squared()).clamp(0.0, 1.0)
+                    } else {
+                        0.0
+                    };
+                    let distance = (pos0 + path * s).norm();
+                    if distance >= threshold {
+                        self.encounters.remove(&(first, second));
+                        continue;
+                    }
+                    let candidate = Approach {
+                        time: start + s * dt,
+                        distance,
+                        speed: (vel0 + (vel1 - vel0) * s).norm(),
+                        reported: false,
+                    };
+                    let approach = self.encounters.entry((first, second)).or_insert(candidate);
+                    if !approach.reported && distance < approach.distance {
+                        *approach = candidate;
+                    }
+                    if !approach.reported && pos1.dot(&vel1) > 0.0 {
+                        approach.reported = true;
+                        event_sender
+                            .send(SimEvent::Encounter {
+                                first,
+                                second,
+                                time: approach.time,
+                                distance: approach.distance,
+                                speed: approach.speed,
+                            })
+                            .unwrap();
+                    }
+                }
+            }
+        }
+
+        let radius = self.metadata.escape_radius;
+        if radius > 0.0 {
+            let (centre_pos, centre_vel) = self.barycentre();
+            let total_mass: f64 = self.bodies.iter().map(|b| b.mass).sum();
+            for (index, body) in self.bodies.iter().enumerate() {
+                let rest = total_mass - body.mass;
+                if rest <= 0.0 {
+                    continue;
+                }
+                //Barycentre of all the other bodies
+                let pos = (centre_pos * total_mass - body.pos * body.mass) / rest;
+                let vel = (centre_vel * total_mass - body.vel * body.mass) / rest;
+                let distance = (body.pos - pos).norm();
+                let speed = (body.vel - vel).norm();
+                let crossed = (before[index].0 - pos).norm() <= radius && distance > radius;
+                let unbound =
+                    0.5 * speed * speed >= self.metadata.interaction_constant * rest / distance;
+                if crossed && unbound {
+                    event_sender
+                        .send(SimEvent::Escape {
+                            index,
+                            time: self.metadata.time,
+                            distance,
+                            speed,
+                        })
+                        .unwrap();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use crate::{Body, OrbitalElements, Res, SimState, AU, GRAVITATIONAL_CONSTANT};
+
+///Mean orbital elements of the planets, major moons and dwarf planets. See the header of the file for the sources
+const TABLE: &str = include_str!("ephemeris.txt");
+pub(crate) const J2000: f64 = 2451545.0;
+pub(crate) const SECONDS_PER_DAY: f64 = 86400.0;
+const DAYS_PER_CENTURY: f64 = 36525.0;
+///The order of the bodies in `SimState::new`. The client names the first bodies after it
+const CLASSIC_ORDER: [&str; 10] = [
+    "sun", "mercury", "venus", "earth", "moon", "mars", "jupiter", "saturn", "uranus", "neptune",
+];
+
+///Converts a calendar date of the form `YYYY-MM-DD` or `YYYY-MM-DDTHH:MM:SS` in the gregorian calendar (UTC)
+///into a Julian date.
+pub fn julian_date(date_str: &str) -> Res<f64> {
+    let date_str = date_str.trim().trim_matches('"');
+    let (date, time) = date_str.split_once('T').unwrap_or((date_str, "00:00:00"));
+    let invalid = || {
+        format!(
+            "Invalid date '{date_str}'. Expected the form 'YYYY-MM-DD' or 'YYYY-MM-DDTHH:MM:SS'."
+        )
+    };
+    //A leading minus belongs to the year
+    let (sign, date) = match date.strip_prefix('-') {
+        Some(d) => (-1, d),
+        None => (1, date),
+    };
+    let date: Vec<&str> = date.split('-').collect();
+    if date.len() != 3 {
+        return Err(invalid().into());
+    }
+    let mut year = sign * date[0].parse::<i64>()?;
+    let mut month = date[1].parse::<i64>()?;
+    let day = date[2].parse::<f64>()?;
+    let mut seconds = 0.0;
+    for (part, scale) in time.split(':').zip([3600.0, 60.0, 1.0]) {
+        seconds += part.parse::<f64>()? * scale;
+    }
+    if !(1..=12).contains(&month) {
+        return Err(invalid().into());
+    }
+    //Algorithm from J. Meeus, "Astronomical Algorithms"
+    if month <= 2 {
+        year -= 1;
+        month += 12;
+    }
+    let a = year.div_euclid(100);
+    let b = 2 - a + a.div_euclid(4);
+    Ok((365.25 * (year + 4716) as f64).floor()
+        + (30.6001 * (month + 1) as f64).floor()
+        + day
+        + b as f64
+        - 1524.5
+        + seconds / SECONDS_PER_DAY)
+}
+
+///Converts a Julian date into a calendar date of the form `YYYY-MM-DDTHH:MM:SS` in the gregorian calendar (UTC)
+pub fn calendar_date(julian_date: f64) -> String {
+    //Algorithm from J. Meeus, "Astronomical Algorithms"
+    let shifted = julian_date + 0.5;
+    let z = shifted.floor();
+    let seconds = ((shifted - z) * SECONDS_PER_DAY).round() as i64;
+    let (z, seconds) = if seconds >= 86400 {
+        (z + 1.0, seconds - 86400)
+    } else {
+        (z, seconds)
+    };
+    let alpha = ((z - 1867216.25) / 36524.25).floor();
+    let a = z + 1.0 + alpha - (alpha / 4.0).floor();
+    let b = a + 1524.0;
+    let c = ((b - 122.1) / 365.25).floor();
+    let d = (365.25 * c).floor();
+    let e = ((b - d) / 30.6001).floor();
+    let day = b - d - (30.6001 * e).floor();
+    let month = if e < 14.0 { e - 1.0 } else { e - 13.0 };
+    let year = if month > 2.0 { c - 4716.0 } else { c - 4715.0 };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+///One row of the table
+struct Entry {
+    name: String,
+    parent: String,
+    mass: f64,
+    density: f64,
+    epoch: f64,
+    ///Length of the unit of the semi-major axis in m
+    unit: f64,
+    ///Semi-major axis, eccentricity, inclination, mean longitude, longitude of periapsis and longitude of the
+    ///ascending node. Angles in degree
+    elements: [f64; 6],
+    ///Change of the elements per Julian century
+    rates: [f64; 6],
+}
+
+impl Entry {
+    fn from_string(line: &str) -> Res<Entry> {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        if columns.len() != 18 {
+            return Err(format!("Invalid ephemeris line '{line}'. Expected 18 columns.").into());
+        }
+        let mut numbers = [0.0; 12];
+        for (number, column) in numbers.iter_mut().zip(&columns[6..]) {
+            *number = column.parse()?;
+        }
+        Ok(Entry {
+            name: columns[0].to_string(),
+            parent: columns[1].to_string(),
+            mass: columns[2].parse()?,
+            density: columns[3].parse()?,
+            epoch: columns[4].parse()?,
+            unit: match columns[5] {
+                "AU" => AU,
+                "km" => 1000.0,
+                x => return Err(format!("Invalid unit '{x}'. Expected 'AU' or 'km'.").into()),
+            },
+            elements: numbers[..6].try_into()?,
+            rates: numbers[6..].try_into()?,
+        })
+    }
+
+    ///The osculating elements at the Julian date `jd`, assuming the mean elements change linearly
+    fn elements_at(&self, jd: f64) -> OrbitalElements {
+        let t = (jd - self.epoch) / DAYS_PER_CENTURY;
+        let [a, e, i, l, peri, node] =
+            [0, 1, 2, 3, 4, 5].map(|k| self.elements[k] + self.rates[k] * t);
+        let mean_anomaly = (l - peri).to_radians();
+        //Solve Kepler's equation with Newton's method
+        let mut eccentric_anomaly = mean_anomaly;
+        for _ in 0..50 {
+            let delta = (eccentric_anomaly - e * eccentric_anomaly.sin() - mean_anomaly)
+                / (1.0 - e * eccentric_anomaly.cos());
+            eccentric_anomaly -= delta;
+            if delta.abs() < 1e-14 {
+                break;
+            }
+        }
+        let true_anomaly = 2.0
+            * ((1.0 + e).sqrt() * (0.5 * eccentric_anomaly).sin())
+                .atan2((1.0 - e).sqrt() * (0.5 * eccentric_anomaly).cos());
+        OrbitalElements {
+            semi_major_axis: a * self.unit,
+            eccentricity: e,
+            inclination: i.to_radians(),
+            longitude_of_ascending_node: node.to_radians(),
+            argument_of_periapsis: (peri - node).to_radians(),
+            true_anomaly,
+        }
+    }
+}
+
+///`planet` followed by its moons of the table at the Julian date `jd`. The barycentre of the system rests in
+///the origin.
+fn planetary_system<'a>(
+    entries: &'a [Entry],
+    planet: &'a Entry,
+    jd: f64,
+) -> Res<Vec<(&'a str, Body)>> {
+    let moons: Vec<&Entry> = entries.iter().filter(|e| e.parent == planet.name).collect();
+    let mut bodies = vec![(planet.name.as_str(), Body::new(planet.mass, planet.density))];
+    for moon in &moons {
+        let mu = GRAVITATIONAL_CONSTANT * (planet.mass + moon.mass);
+        let (r, v) = moon.elements_at(jd).to_state(mu)?;
+        bodies.push((
+            &moon.name,
+            Body::new(moon.mass, moon.density)
+                .set_position(r)
+                .set_velocity(v),
+        ));
+    }
+    let (names, bodies): (Vec<&str>, Vec<Body>) = bodies.into_iter().unzip();
+    let mut system = SimState::new(0.0, 1.0);
+    system.bodies = bodies;
+    system.recenter();
+    Ok(names.into_iter().zip(system.bodies).collect())
+}
+
+///The planet `name` and its moons of the table at the Julian date `jd`, with the barycentre resting in the
+///origin.
+///
+/// # Error
+///
+/// Fails if the planet is not part of the table.
+pub fn planetary_moons(name: &str, jd: f64) -> Res<Vec<Body>> {
+    let entries = entries()?;
+    let planet = entries
+        .iter()
+        .find(|e| e.name == name && e.parent == "sun")
+        .ok_or(format!(
+            "The planet '{name}' is not part of the ephemeris table."
+        ))?;
+    Ok(planetary_system(&entries, planet, jd)?
+        .into_iter()
+        .map(|(_, body)| body)
+        .collect())
+}
+
+fn entries() -> Res<Vec<Entry>> {
+    TABLE
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(Entry::from_string)
+        .collect()
+}
+
+///The sun, planets, major moons and dwarf planets at the Julian date `jd` in the ecliptic frame of J2000.
+///The sun rests in the origin. The first bodies follow the order of `SimState::new`, the others the order of
+///the table with the moons following their planet.
+pub fn solar_system(jd: f64) -> Res<Vec<Body>> {
+    let entries = entries()?;
+    let sun = Body::sun();
+    let mut bodies: Vec<(&str, Body)> = vec![("sun", sun.clone())];
+    for planet in entries.iter().filter(|e| e.parent == "sun") {
+        let system = planetary_system(&entries, planet, jd)?;
+        let system_mass: f64 = system.iter().map(|(_, b)| b.mass).sum();
+        //The elements of the planet describe the barycentre of its system
+        let (pos, vel) = planet
+            .elements_at(jd)
+            .to_state(GRAVITATIONAL_CONSTANT * (sun.mass + system_mass))?;
+        bodies.extend(system.into_iter().map(|(name, body)| {
+            let (body_pos, body_vel) = (body.pos, body.vel);
+            (
+                name,
+                body.set_position(pos + body_pos)
+                    .set_velocity(vel + body_vel),
+            )
+        }));
+    }
+    let rank = |name: &str| {
+        CLASSIC_ORDER
+            .iter()
+            .position(|n| *n == name)
+            .unwrap_or(CLASSIC_ORDER.len())
+    };
+    //Stable sort, so the bodies outside of the classic order keep the order of the table
+    bodies.sort_by_key(|(name, _)| rank(name));
+    Ok(bodies.into_iter().map(|(_, body)| body).collect())
+}
+
+impl SimState {
+    ///The sun, planets, major moons and dwarf planets at the calendar date `date_str` (see `julian_date`).
+    ///Apart from the bodies it matches `SimState::new`.
+    ///
+    /// # Error
+    ///
+    /// Fails for dates in an invalid form.
+    pub fn at_epoch(
+        date_str: &str,
+        target_time_per_step_s: f64,
+        time_scaling: f64,
+    ) -> Res<SimState> {
+        let jd = julian_date(date_str)?;
+        let mut sim = SimState::new(target_time_per_step_s, time_scaling);
+        sim.bodies = solar_system(jd)?;
+        sim.set_epoch(Some(jd));
+        Ok(sim)
+    }
+
+    ///Julian date at the start of the simulation, if the bodies belong to a calendar date
+    pub fn epoch(&self) -> Option<f64> {
+        self.metadata.epoch
+    }
+
+    ///Sets the Julian date at the start of the simulation
+    pub fn set_epoch(&mut self, epoch: Option<f64>) {
+        self.metadata.epoch = epoch;
+    }
+
+    ///The current calendar date of the simulation of the form `YYYY-MM-DDTHH:MM:SS`, if it has an epoch
+    pub fn date(&self) -> Option<String> {
+        self.epoch()
+            .map(|jd| calendar_date(jd + self.time() / SECONDS_PER_DAY))
+    }
+}
@@ -0,0 +1,295 @@
+use std::sync::Arc;
+
+use crate::{Body, Res, C};
+use na::Vector3;
+
+///A force acting on the bodies besides the gravitation between them. Force models are registered on a
+///`SimState` with `add_force`, their parameters are part of the metadata as `"<name>.<parameter>"`.
+///
+///Forces that are not conservative change the total energy, so they show up in the energy drift.
+pub trait ForceModel: Send + Sync {
+    ///Unique name, used as prefix of the parameters in the metadata
+    fn name(&self) -> &str;
+    ///Acceleration of the body at index `i` of `bodies` caused by this force
+    fn acceleration(&self, bodies: &[Body], i: usize) -> Vector3<f64>;
+    ///The current parameters as pairs of name and value
+    fn parameters(&self) -> Vec<(&'static str, f64)>;
+    ///Sets the parameter `name` to `value`
+    ///
+    /// # Error
+    ///
+    /// Fails if the force has no parameter `name`.
+    fn set_parameter(&mut self, name: &str, value: f64) -> Res<()>;
+    ///Keeps the parameters that are indices of bodies pointing at the same bodies. `moved` maps the old index
+    ///of a body to the new one, or to `None` if the body is gone. Returns the parameters of bodies that are
+    ///gone, which are reset to 0.
+    fn reindex(&mut self, _moved: &dyn Fn(usize) -> Option<usize>) -> Vec<&'static str> {
+        vec![]
+    }
+    fn clone_box(&self) -> Box<dyn ForceModel>;
+}
+
+impl Clone for Box<dyn ForceModel> {
+    fn clone(&self) -> Box<dyn ForceModel> {
+        self.clone_box()
+    }
+}
+
+fn invalid_parameter(force: &str, name: &str) -> Res<()> {
+    Err(format!("Invalid parameter. The force '{force}' has no parameter '{name}'.").into())
+}
+
+///The index of a body given as parameter
+fn index(value: f64) -> usize {
+    value.max(0.0) as usize
+}
+
+///Moves the index parameter `name` along with its body, see `ForceModel::reindex`
+fn move_index(
+    index: &mut usize,
+    name: &'static str,
+    moved: &dyn Fn(usize) -> Option<usize>,
+) -> Vec<&'static str> {
+    match moved(*index) {
+        Some(k) => {
+            *index = k;
+            vec![]
+        }
+        None => {
+            *index = 0;
+            vec![name]
+        }
+    }
+}
+
+///Pressure of the light of the body at `source` on the cross section of all other bodies. Pushes small bodies
+///like dust and spacecraft away from the star.
+#[derive(Clone)]
+pub struct RadiationPressure {
+    ///Index of the radiating body
+    pub source: usize,
+    ///Emitted power in W
+    pub luminosity: f64,
+    ///Fraction of the momentum of the light transferred to the body. 1 for a perfect absorber, 2 for a mirror
+    pub efficiency: f64,
+}
+
+impl RadiationPressure {
+    ///The sun at index 0 radiating on perfect absorbers
+    pub fn sun() -> RadiationPressure {
+        RadiationPressure {
+            source: 0,
+            luminosity: 3.828e26,
+            efficiency: 1.0,
+        }
+    }
+}
+
+impl ForceModel for RadiationPressure {
+    fn name(&self) -> &str {
+        "radiation_pressure"
+    }
+
+    fn acceleration(&self, bodies: &[Body], i: usize) -> Vector3<f64> {
+        let body = &bodies[i];
+        match bodies.get(self.source) {
+            Some(source) if i != self.source && body.mass > 0.0 => {
+                let r = body.pos - source.pos;
+                let distance = r.norm();
+                r / distance * self.luminosity * self.efficiency * body.radius.powi(2)
+                    / (4.0 * C * distance.powi(2) * body.mass)
+            }
+            _ => Vector3::zeros(),
+        }
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("source", self.source as f64),
+            ("luminosity", self.luminosity),
+            ("efficiency", self.efficiency),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f64) -> Res<()> {
+        match name {
+            "source" => self.source = index(value),
+            "luminosity" => self.luminosity = value,
+            "efficiency" => self.efficiency = value,
+            _ => return invalid_parameter(self.name(), name),
+        }
+        Ok(())
+    }
+
+    fn reindex(&mut self, moved: &dyn Fn(usize) -> Option<usize>) -> Vec<&'static str> {
+        move_index(&mut self.source, "source", moved)
+    }
+
+    fn clone_box(&self) -> Box<dyn ForceModel> {
+        Box::new(self.clone())
+    }
+}
+
+///Drag of an exponential atmosphere around the body at `planet`, which rotates with the planet's velocity.
+///Slows down bodies flying through it, so low orbits decay.
+#[derive(Clone)]
+pub struct AtmosphericDrag {
+    ///Index of the body with the atmosphere
+    pub planet: usize,
+    ///Density of the atmosphere at the surface in kg/m^3
+    pub surface_density: f64,
+    ///Height over which the density falls by a factor of e in m
+    pub scale_height: f64,
+    pub drag_coefficient: f64,
+}
+
+impl AtmosphericDrag {
+    ///The atmosphere of the earth at index `planet`
+    pub fn earth(planet: usize) -> AtmosphericDrag {
+        AtmosphericDrag {
+            planet,
+            surface_density: 1.225,
+            scale_height: 8500.0,
+            drag_coefficient: 2.2,
+        }
+    }
+}
+
+impl ForceModel for AtmosphericDrag {
+    fn name(&self) -> &str {
+        "atmospheric_drag"
+    }
+
+    fn acceleration(&self, bodies: &[Body], i: usize) -> Vector3<f64> {
+        let body = &bodies[i];
+        match bodies.get(self.planet) {
+            Some(planet) if i != self.planet && body.mass > 0.0 => {
+                let height = (body.pos - planet.pos).norm() - planet.radius;
+                let density = self.surface_density * (-height.max(0.0) / self.scale_height).exp();
+                let v = body.vel - planet.vel;
+                -0.5 * self.drag_coefficient
+                    * density
+                    * std::f64::consts::PI
+                    * body.radius.powi(2)
+                    * v.norm()
+                    * v
+                    / body.mass
+            }
+            _ => Vector3::zeros(),
+        }
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("planet", self.planet as f64),
+            ("surface_density", self.surface_density),
+            ("scale_height", self.scale_height),
+            ("drag_coefficient", self.drag_coefficient),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f64) -> Res<()> {
+        match name {
+            "planet" => self.planet = index(value),
+            "surface_density" => self.surface_density = value,
+            "scale_height" => self.scale_height = value,
+            "drag_coefficient" => self.drag_coefficient = value,
+            _ => return invalid_parameter(self.name(), name),
+        }
+        Ok(())
+    }
+
+    fn reindex(&mut self, moved: &dyn Fn(usize) -> Option<usize>) -> Vec<&'static str> {
+        move_index(&mut self.planet, "planet", moved)
+    }
+
+    fn clone_box(&self) -> Box<dyn ForceModel> {
+        Box::new(self.clone())
+    }
+}
+
+///The same acceleration for every body, including test particles
+#[derive(Clone)]
+pub struct ConstantField {
+    ///Acceleration in m/s^2
+    pub field: Vector3<f64>,
+}
+
+impl ForceModel for ConstantField {
+    fn name(&self) -> &str {
+        "constant_field"
+    }
+
+    fn acceleration(&self, _bodies: &[Body], _i: usize) -> Vector3<f64> {
+        self.field
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        vec![
+            ("x", self.field.x),
+            ("y", self.field.y),
+            ("z", self.field.z),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f64) -> Res<()> {
+        match name {
+            "x" => self.field.x = value,
+            "y" => self.field.y = value,
+            "z" => self.field.z = value,
+            _ => return invalid_parameter(self.name(), name),
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ForceModel> {
+        Box::new(self.clone())
+    }
+}
+
+///Signature of a pairwise law. Returns the acceleration per unit mass of the source at the given distance,
+///positive values attract.
+pub type Law = dyn Fn(f64) -> f64 + Send + Sync;
+
+///A user defined force between every pair of massive bodies, scaled by `strength`. Like gravitation it is
+///proportional to the mass of the source and points along the line between both bodies.
+#[derive(Clone)]
+pub struct PairwiseLaw {
+    pub name: String,
+    pub strength: f64,
+    pub law: Arc<Law>,
+}
+
+impl ForceModel for PairwiseLaw {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn acceleration(&self, bodies: &[Body], i: usize) -> Vector3<f64> {
+        let mut acc = Vector3::zeros();
+        for (k, other) in bodies.iter().enumerate() {
+            if k != i && !other.is_particle() {
+                let r = other.pos - bodies[i].pos;
+                let distance = r.norm();
+                acc += r / distance * (self.strength * (self.law)(distance) * other.mass);
+            }
+        }
+        acc
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, f64)> {
+        vec![("strength", self.strength)]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f64) -> Res<()> {
+        match name {
+            "strength" => self.strength = value,
+            _ => return invalid_parameter(self.name(), name),
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ForceModel> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,264 @@
+use std::{f64::consts::PI, fmt::Display};
+
+use crate::{
+    Body, ForceSolver, OrbitalElements, Res, SimState, Softening, AU, GRAVITATIONAL_CONSTANT,
+    SOLAR_MASS,
+};
+use na::Vector3;
+
+const SOLAR_DENSITY: f64 = 1410.0;
+///Radii beyond this many scale lengths are drawn again, so single outliers do not blow up the view
+const MAX_SCALE_LENGTHS: f64 = 10.0;
+
+///Small deterministic random number generator (SplitMix64), so every scenario can be rebuilt from its seed
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    ///Uniformly distributed in [0, 1)
+    pub(crate) fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    ///Uniformly distributed in [min, max)
+    pub(crate) fn range(&mut self, min: f64, max: f64) -> f64 {
+        min + (max - min) * self.uniform()
+    }
+
+    ///Normally distributed with mean 0 and standard deviation 1
+    pub(crate) fn normal(&mut self) -> f64 {
+        let u = 1.0 - self.uniform();
+        (-2.0 * u.ln()).sqrt() * (2.0 * PI * self.uniform()).cos()
+    }
+
+    ///A random direction, uniformly distributed on the unit sphere
+    pub(crate) fn direction(&mut self) -> Vector3<f64> {
+        let z = self.range(-1.0, 1.0);
+        let angle = self.range(0.0, 2.0 * PI);
+        let r = (1.0 - z * z).sqrt();
+        Vector3::new(r * angle.cos(), r * angle.sin(), z)
+    }
+}
+
+///Procedurally generated scenarios that can be selected at start up
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Generator {
+    ///The base system with a belt of test particles between Mars and Jupiter
+    AsteroidBelt,
+    ///A star cluster in virial equilibrium
+    Plummer,
+    ///A rotating exponential disc of stars around a heavy center
+    DiscGalaxy,
+    ///A young star surrounded by planetesimals
+    ProtoplanetaryDisc,
+}
+
+impl Display for Generator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Generator::AsteroidBelt => "asteroid_belt",
+            Generator::Plummer => "plummer",
+            Generator::DiscGalaxy => "disc_galaxy",
+            Generator::ProtoplanetaryDisc => "protoplanetary_disc",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Generator {
+    pub fn from_string(generator_str: &str) -> Res<Generator> {
+        match generator_str.trim().trim_matches('"') {
+            "asteroid_belt" => Ok(Generator::AsteroidBelt),
+            "plummer" => Ok(Generator::Plummer),
+            "disc_galaxy" => Ok(Generator::DiscGalaxy),
+            "protoplanetary_disc" => Ok(Generator::ProtoplanetaryDisc),
+            x => Err(format!(
+                "Invalid generator. Expected 'asteroid_belt', 'plummer', 'disc_galaxy' or 'protoplanetary_disc', but found {x}."
+            )
+            .into()),
+        }
+    }
+
+    ///Builds the scenario with its default parameters, scaled to fit the view of the client. The asteroid belt
+    ///is added to the bodies of `base`, the other scenarios replace them. Everything else is kept from `base`.
+    pub fn build(&self, base: SimState, seed: u64) -> SimState {
+        let mut sim = base;
+        match self {
+            Generator::AsteroidBelt => {
+                sim.add_asteroid_belt(0, 2000, 2.1 * AU, 3.3 * AU, seed);
+            }
+            Generator::Plummer => {
+                sim.bodies = plummer(200, 200.0 * SOLAR_MASS, 20.0 * AU, seed);
+            }
+            Generator::DiscGalaxy => {
+                sim.bodies = disc_galaxy(
+                    2000,
+                    2000.0 * SOLAR_MASS,
+                    1000.0 * SOLAR_MASS,
+                    15.0 * AU,
+                    seed,
+                );
+                //Collisionless stars, so close encounters are softened
+                sim.metadata.solver = ForceSolver::BarnesHut;
+                sim.metadata.softening = Softening::Plummer;
+                sim.metadata.softening_length = 0.5 * AU;
+            }
+            Generator::ProtoplanetaryDisc => {
+                sim.bodies = protoplanetary_disc(300, 0.4 * AU, 4.0 * AU, 6e25, seed);
+            }
+        }
+        //The sampled bodies drift a little, the belt keeps the frame of `base`
+        if *self != Generator::AsteroidBelt {
+            sim.recenter();
+        }
+        sim.set_epoch(None);
+        sim.baseline = None;
+        sim
+    }
+}
+
+///Star cluster with the density profile of a Plummer sphere. Positions and velocities are drawn from the
+///distribution function, so the cluster starts in virial equilibrium around the origin. `SimState::recenter`
+///removes the small drift of the sampled bodies.
+pub fn plummer(count: usize, total_mass: f64, scale_radius: f64, seed: u64) -> Vec<Body> {
+    let mut rng = Rng::new(seed);
+    (0..count)
+        .map(|_| {
+            let radius = loop {
+                let u = rng.uniform();
+                if u > 0.0 {
+                    let r = scale_radius / (u.powf(-2.0 / 3.0) - 1.0).sqrt();
+                    if r < MAX_SCALE_LENGTHS * scale_radius {
+                        break r;
+                    }
+                }
+            };
+            //Fraction of the escape velocity by rejection sampling of q^2 (1 - q^2)^3.5
+            let q = loop {
+                let q = rng.uniform();
+                if 0.1 * rng.uniform() < q * q * (1.0 - q * q).powf(3.5) {
+                    break q;
+                }
+            };
+            let escape = (2.0 * GRAVITATIONAL_CONSTANT * total_mass
+                / (radius * radius + scale_radius * scale_radius).sqrt())
+            .sqrt();
+            Body::new(total_mass / count as f64, SOLAR_DENSITY)
+                .set_position(rng.direction() * radius)
+                .set_velocity(rng.direction() * q * escape)
+        })
+        .collect()
+}
+
+///Rotating disc of stars with an exponential surface density around a central mass, which comes first.
+///The stars move on circular orbits through the enclosed mass with a small velocity dispersion.
+pub fn disc_galaxy(
+    count: usize,
+    disc_mass: f64,
+    central_mass: f64,
+    scale_length: f64,
+    seed: u64,
+) -> Vec<Body> {
+    let mut rng = Rng::new(seed);
+    let mut bodies = vec![Body::new(central_mass, SOLAR_DENSITY)];
+    for _ in 0..count {
+        //The radius of an exponential disc follows a gamma distribution of shape 2
+        let radius = loop {
+            let r = -scale_length * ((1.0 - rng.uniform()) * (1.0 - rng.uniform())).ln();
+            if r > 0.0 && r < MAX_SCALE_LENGTHS * scale_length {
+                break r;
+            }
+        };
+        let angle = rng.range(0.0, 2.0 * PI);
+        let x = radius / scale_length;
+        let enclosed = central_mass + disc_mass * (1.0 - (1.0 + x) * (-x).exp());
+        let speed = (GRAVITATIONAL_CONSTANT * enclosed / radius).sqrt();
+        let radial = Vector3::new(angle.cos(), angle.sin(), 0.0);
+        let dispersion = Vector3::new(rng.normal(), rng.normal(), rng.normal()) * 0.05 * speed;
+        bodies.push(
+            Body::new(disc_mass / count as f64, SOLAR_DENSITY)
+                .set_position(radial * radius + Vector3::z() * rng.normal() * 0.05 * scale_length)
+                .set_velocity(Vector3::z().cross(&radial) * speed + dispersion),
+        );
+    }
+    bodies
+}
+
+///A sun-like star with `count` planetesimals between `inner` and `outer` on nearly circular and coplanar
+///orbits. The surface density falls with the distance to the power of -3/2, the masses vary by a factor of 3.
+pub fn protoplanetary_disc(
+    count: usize,
+    inner: f64,
+    outer: f64,
+    disc_mass: f64,
+    seed: u64,
+) -> Vec<Body> {
+    let mut rng = Rng::new(seed);
+    let star = Body::new(SOLAR_MASS, SOLAR_DENSITY);
+    let mut bodies = vec![star.clone()];
+    for _ in 0..count {
+        let elements = OrbitalElements {
+            semi_major_axis: (inner.sqrt() + rng.uniform() * (outer.sqrt() - inner.sqrt())).powi(2),
+            eccentricity: rng.range(0.0, 0.02),
+            inclination: rng.range(0.0, 0.01),
+            longitude_of_ascending_node: rng.range(0.0, 2.0 * PI),
+            argument_of_periapsis: rng.range(0.0, 2.0 * PI),
+            true_anomaly: rng.range(0.0, 2.0 * PI),
+        };
+        let mass = disc_mass / count as f64 * rng.range(0.5, 1.5);
+        bodies.push(
+            Body::from_elements(mass, 3000.0, &star, &elements)
+                .expect("Elliptic orbits are always reachable."),
+        );
+    }
+    bodies
+}
+
+impl SimState {
+    ///Adds `count` test particles on orbits around the body at index `parent` with semi-major axes between
+    ///`inner` and `outer`, small eccentricities and inclinations to its x-y-plane.
+    pub fn add_asteroid_belt(
+        &mut self,
+        parent: usize,
+        count: usize,
+        inner: f64,
+        outer: f64,
+        seed: u64,
+    ) {
+        let mut rng = Rng::new(seed);
+        let parent = self.bodies[parent].clone();
+        let mu = self.metadata.interaction_constant * parent.mass;
+        for _ in 0..count {
+            let elements = OrbitalElements {
+                semi_major_axis: rng.range(inner, outer),
+                eccentricity: rng.range(0.0, 0.15),
+                inclination: rng.normal().abs() * 0.1,
+                longitude_of_ascending_node: rng.range(0.0, 2.0 * PI),
+                argument_of_periapsis: rng.range(0.0, 2.0 * PI),
+                true_anomaly: rng.range(0.0, 2.0 * PI),
+            };
+            let (r, v) = elements
+                .to_state(mu)
+                .expect("Elliptic orbits are always reachable.");
+            self.add_body(
+                Body::particle()
+                    .set_position(parent.pos + r)
+                    .set_velocity(parent.vel + v),
+            );
+        }
+        self.baseline = None;
+    }
+}
@@ -0,0 +1,116 @@
+use std::{cmp::Ordering, f64::consts::PI, fmt::Display};
+
+use crate::{lagrange::hill_radius, massive_count, Body, OrbitalElements, SimState};
+
+///The orbit of a body around its parent in the hierarchy
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Orbit {
+    ///Index of the body that is orbited
+    pub parent: usize,
+    pub semi_major_axis: f64,
+    pub eccentricity: f64,
+    ///Time for one revolution in s
+    pub period: f64,
+}
+
+impl Display for Orbit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{\"parent\": {}, \"semi_major_axis\": {}, \"eccentricity\": {}, \"period\": {}}}",
+            self.parent, self.semi_major_axis, self.eccentricity, self.period
+        )
+    }
+}
+
+///Who orbits whom. Every massive body either orbits a heavier body or is a root of the hierarchy, like the sun.
+#[derive(Clone, Debug, Default)]
+pub struct Hierarchy {
+    ///The orbit of the massive body at the same index, `None` for roots
+    pub orbits: Vec<Option<Orbit>>,
+}
+
+impl Display for Hierarchy {
+    ///The orbits as JSON array, with `null` for roots
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let orbits: Vec<String> = self
+            .orbits
+            .iter()
+            .map(|o| o.map_or(String::from("null"), |o| o.to_string()))
+            .collect();
+        write!(f, "[{}]", orbits.join(", "))
+    }
+}
+
+impl Hierarchy {
+    ///Finds the parent of every massive body of `bodies`. A body orbits the heavier body it is gravitationally
+    ///bound to and which holds it inside of its Hill sphere. If several do, the one with the smallest Hill sphere
+    ///wins, so the moon orbits the earth and not the sun. Roots have an infinite Hill sphere.
+    pub fn detect(bodies: &[Body], interaction_constant: f64) -> Hierarchy {
+        let bodies = &bodies[..massive_count(bodies)];
+        //Heavier bodies are placed first, so their parents are known when they are candidates themselves.
+        //Equal masses are ordered by index
+        let mut order: Vec<usize> = (0..bodies.len()).collect();
+        order.sort_by(|&a, &b| {
+            bodies[b]
+                .mass
+                .partial_cmp(&bodies[a].mass)
+                .unwrap_or(Ordering::Equal)
+                .then(a.cmp(&b))
+        });
+        let mut orbits: Vec<Option<Orbit>> = vec![None; bodies.len()];
+        let mut hill_radii = vec![f64::INFINITY; bodies.len()];
+        for (rank, &i) in order.iter().enumerate() {
+            let body = &bodies[i];
+            let mut best: Option<(f64, Orbit)> = None;
+            for &k in &order[..rank] {
+                let parent = &bodies[k];
+                let r = body.pos - parent.pos;
+                if r.norm() >= hill_radii[k]
+                    || matches!(best, Some((hill, _)) if hill <= hill_radii[k])
+                {
+                    continue;
+                }
+                let v = body.vel - parent.vel;
+                let mu = interaction_constant * (body.mass + parent.mass);
+                //Specific orbital energy, negative for bound orbits
+                if 0.5 * v.norm_squared() - mu / r.norm() < 0.0 {
+                    let elements = OrbitalElements::from_state(&r, &v, mu);
+                    best = Some((
+                        hill_radii[k],
+                        Orbit {
+                            parent: k,
+                            semi_major_axis: elements.semi_major_axis,
+                            eccentricity: elements.eccentricity,
+                            period: 2.0 * PI * (elements.semi_major_axis.powi(3) / mu).sqrt(),
+                        },
+                    ));
+                }
+            }
+            if let Some((_, orbit)) = best {
+                hill_radii[i] = hill_radius(body, &bodies[orbit.parent], interaction_constant);
+                orbits[i] = Some(orbit);
+            }
+        }
+        Hierarchy { orbits }
+    }
+
+    ///Index of the body that the body at `index` orbits
+    pub fn parent(&self, index: usize) -> Option<usize> {
+        self.orbits.get(index).copied().flatten().map(|o| o.parent)
+    }
+
+    ///Indices of the bodies that orbit the body at `index`
+    pub fn children(&self, index: usize) -> Vec<usize> {
+        (0..self.orbits.len())
+            .filter(|&i| self.parent(i) == Some(index))
+            .collect()
+    }
+}
+
+impl SimState {
+    ///Who currently orbits whom among the massive bodies, see `Hierarchy::detect`
+    pub fn hierarchy(&self) -> Hierarchy {
+        Hierarchy::detect(&self.bodies, self.metadata.interaction_constant)
+    }
+}
@@ -0,0 +1,238 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use crate::{ephemeris::SECONDS_PER_DAY, Body, Res, SimState, AU};
+use na::Vector3;
+use regex::Regex;
+
+///Two exports count as the same epoch if their Julian dates differ by less than this
+const EPOCH_TOLERANCE_DAYS: f64 = 1e-6;
+///TDB runs ahead of UTC by 32.184 s plus the leap seconds, of which there are 37 since 2017. Earlier epochs are
+///off by the leap seconds added since, i.e. by at most a minute
+const TDB_MINUS_UTC_S: f64 = 69.184;
+
+///One row of a JPL Horizons vector table, converted to SI units
+#[derive(Clone, Debug)]
+pub struct HorizonsRecord {
+    ///The target body as written in the header, e.g. `Earth (399)`
+    pub target: String,
+    ///Barycentric dynamical time (TDB) as Julian date
+    pub julian_date: f64,
+    pub pos: Vector3<f64>,
+    pub vel: Vector3<f64>,
+}
+
+///Reads the value of a header line of the form `key: value {comment}`
+fn header_value<'a>(export: &'a str, key: &str) -> Option<&'a str> {
+    export.lines().find_map(|line| {
+        let value = line
+            .trim()
+            .strip_prefix(key)?
+            .trim_start()
+            .strip_prefix(':')?;
+        Some(value.split('{').next().unwrap().trim())
+    })
+}
+
+///The length of the distance unit in m and of the time unit in s of an `Output units` header value
+fn units(units_str: &str) -> Res<(f64, f64)> {
+    let (length, time) = units_str.split_once('-').ok_or(format!(
+        "Invalid output units '{units_str}'. Expected e.g. 'KM-S'."
+    ))?;
+    let length = match length.trim() {
+        "KM" => 1000.0,
+        "AU" => AU,
+        x => return Err(format!("Invalid length unit '{x}'. Expected 'KM' or 'AU'.").into()),
+    };
+    let time = match time.trim() {
+        "S" => 1.0,
+        "D" => 86400.0,
+        x => return Err(format!("Invalid time unit '{x}'. Expected 'S' or 'D'.").into()),
+    };
+    Ok((length, time))
+}
+
+impl HorizonsRecord {
+    ///Parses all rows between `$$SOE` and `$$EOE` of a Horizons vector table export. Both the text and the
+    ///CSV format are understood. Without an `Output units` header, km and km/s are assumed.
+    ///
+    /// # Error
+    ///
+    /// Fails if the export contains no rows or a row misses one of the components of the state vector.
+    pub fn from_string(export: &str) -> Res<Vec<HorizonsRecord>> {
+        let target = header_value(export, "Target body name")
+            .unwrap_or("")
+            .to_string();
+        let (length, time) = match header_value(export, "Output units") {
+            Some(u) => units(u)?,
+            None => (1000.0, 1.0),
+        };
+        let (header, rest) = export
+            .split_once("$$SOE")
+            .ok_or("Invalid Horizons export. Missing '$$SOE'.")?;
+        let table = rest.split("$$EOE").next().unwrap();
+
+        //Both formats are reduced to a list of (Julian date, column name -> value)
+        let mut rows: Vec<(f64, HashMap<String, f64>)> = vec![];
+        if table.contains(',') {
+            let columns: Vec<String> = match header
+                .lines()
+                .rev()
+                .find(|l| l.trim_start().starts_with("JDTDB"))
+            {
+                Some(l) => l.split(',').map(|c| c.trim().to_string()).collect(),
+                None => [
+                    "JDTDB",
+                    "Calendar Date (TDB)",
+                    "X",
+                    "Y",
+                    "Z",
+                    "VX",
+                    "VY",
+                    "VZ",
+                ]
+                .map(String::from)
+                .to_vec(),
+            };
+            for line in table.lines().filter(|l| !l.trim().is_empty()) {
+                let mut values = HashMap::new();
+                for (column, value) in columns.iter().zip(line.split(',')) {
+                    if let Ok(v) = value.trim().parse::<f64>() {
+                        values.insert(column.clone(), v);
+                    }
+                }
+                let jd = *values
+                    .get("JDTDB")
+                    .ok_or(format!("Invalid Horizons row '{line}'. Missing 'JDTDB'."))?;
+                rows.push((jd, values));
+            }
+        } else {
+            let pair = Regex::new(r"([A-Z]+)\s*=\s*([-+]?[0-9.]+(?:E[-+]?[0-9]+)?)").unwrap();
+            for line in table.lines().filter(|l| !l.trim().is_empty()) {
+                //Each row starts with a line of the form `2460310.500000000 = A.D. 2024-Jan-01 00:00:00.0000 TDB`
+                if line.starts_with(|c: char| c.is_ascii_digit()) {
+                    let jd = line.split('=').next().unwrap().trim().parse::<f64>()?;
+                    rows.push((jd, HashMap::new()));
+                    continue;
+                }
+                let (_, values) = rows
+                    .last_mut()
+                    .ok_or(format!("Invalid Horizons row '{line}'. Missing the date."))?;
+                for capture in pair.captures_iter(line) {
+                    values.insert(capture[1].to_string(), capture[2].parse::<f64>()?);
+                }
+            }
+        }
+        if rows.is_empty() {
+            return Err(
+                "Invalid Horizons export. The table between '$$SOE' and '$$EOE' is empty.".into(),
+            );
+        }
+
+        rows.into_iter()
+            .map(|(julian_date, values)| {
+                let mut state = [0.0; 6];
+                for (component, key) in state.iter_mut().zip(["X", "Y", "Z", "VX", "VY", "VZ"]) {
+                    *component = *values.get(key).ok_or(format!(
+                        "Invalid Horizons row at JD {julian_date}. Missing '{key}'."
+                    ))?;
+                }
+                Ok(HorizonsRecord {
+                    target: target.clone(),
+                    julian_date,
+                    pos: Vector3::new(state[0], state[1], state[2]) * length,
+                    vel: Vector3::new(state[3], state[4], state[5]) * length / time,
+                })
+            })
+            .collect()
+    }
+
+    ///The name of the target in lower case without the id, e.g. `earth` for `Earth (399)`
+    pub fn name(&self) -> String {
+        self.target.split('(').next().unwrap().trim().to_lowercase()
+    }
+
+    ///The Horizons id of the target, e.g. `399` for `Earth (399)`
+    pub fn id(&self) -> Option<&str> {
+        let (_, id) = self.target.split_once('(')?;
+        id.split(')').next().map(str::trim)
+    }
+}
+
+///Parses a table of physical properties. Each line holds a name or Horizons id, the mass in kg and the density
+///in kg/m^3 separated by whitespace. Names are not case sensitive and lines starting with `#` are ignored.
+pub fn physical_properties(table: &str) -> Res<HashMap<String, (f64, f64)>> {
+    let mut properties = HashMap::new();
+    for line in table
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+    {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        if columns.len() != 3 {
+            return Err(
+                format!("Invalid property line '{line}'. Expected 'name mass density'.").into(),
+            );
+        }
+        properties.insert(
+            columns[0].to_lowercase(),
+            (columns[1].parse::<f64>()?, columns[2].parse::<f64>()?),
+        );
+    }
+    Ok(properties)
+}
+
+impl Body {
+    ///Creates a body with the state vector of `record`
+    pub fn from_horizons(record: &HorizonsRecord, mass: f64, density: f64) -> Body {
+        Body::new(mass, density)
+            .set_position(record.pos)
+            .set_velocity(record.vel)
+    }
+}
+
+impl SimState {
+    ///Builds a simulation from Horizons vector table exports, one file per body. The first row of each file is
+    ///used, so all files have to start at the same epoch. Mass and density are looked up in the property table
+    ///`properties_path` (see `physical_properties`) by the target name and then by its id.
+    ///Apart from the bodies it matches `SimState::new`. The epoch of the exports is given in TDB and converted
+    ///to the UTC of `SimState::epoch`.
+    ///
+    /// # Error
+    ///
+    /// Fails if a file cannot be read or parsed, the epochs differ or a target is missing in the property table.
+    pub fn from_horizons<P: AsRef<Path>, Q: AsRef<Path>>(
+        vector_paths: &[P],
+        properties_path: Q,
+        target_time_per_step_s: f64,
+        time_scaling: f64,
+    ) -> Res<SimState> {
+        let properties = physical_properties(&fs::read_to_string(properties_path)?)?;
+        let mut sim = SimState::new(target_time_per_step_s, time_scaling);
+        sim.bodies = vec![];
+        let mut epoch: Option<f64> = None;
+        for path in vector_paths {
+            let record = HorizonsRecord::from_string(&fs::read_to_string(path)?)?.remove(0);
+            match epoch {
+                Some(jd) if (jd - record.julian_date).abs() > EPOCH_TOLERANCE_DAYS => {
+                    return Err(format!(
+                        "The export of '{}' starts at JD {}, but the others at JD {jd}.",
+                        record.target, record.julian_date
+                    )
+                    .into());
+                }
+                _ => epoch = Some(record.julian_date),
+            }
+            let (mass, density) = properties
+                .get(&record.name())
+                .or_else(|| record.id().and_then(|id| properties.get(id)))
+                .ok_or(format!(
+                    "No mass and density for '{}' in the property table.",
+                    record.target
+                ))?;
+            sim.bodies
+                .push(Body::from_horizons(&record, *mass, *density));
+        }
+        sim.set_epoch(epoch.map(|jd| jd - TDB_MINUS_UTC_S / SECONDS_PER_DAY));
+        Ok(sim)
+    }
+}
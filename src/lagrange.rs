@@ -0,0 +1,187 @@
+use std::fmt::Display;
+
+use crate::{
+    diagnostics::json_vector, hierarchy::Hierarchy, Body, OrbitalElements, Res, SimState, Snapshot,
+};
+use na::Vector3;
+
+///The equilibrium points of the restricted three body problem of a pair of bodies and the Hill sphere of the
+///lighter one. They are taken from the current separation of the pair, which is exact for circular orbits.
+#[derive(Clone, Debug)]
+pub struct LagrangePoints {
+    pub primary: usize,
+    pub secondary: usize,
+    ///L1 to L5. L1 lies between both bodies, L2 behind the secondary and L3 behind the primary. L4 leads the
+    ///secondary on its orbit by 60°, L5 trails it.
+    pub points: [Vector3<f64>; 5],
+    ///Radius of the Hill sphere of the secondary around the primary
+    pub hill_radius: f64,
+    ///Radius of the Hill sphere of every massive body around the body it orbits in the `Hierarchy`, `None` for
+    ///roots
+    pub hill_radii: Vec<Option<f64>>,
+}
+
+impl Display for LagrangePoints {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let points: Vec<String> = self.points.iter().map(json_vector).collect();
+        let hill_radii: Vec<String> = self
+            .hill_radii
+            .iter()
+            .map(|r| r.map_or(String::from("null"), |r| r.to_string()))
+            .collect();
+        write!(
+            f,
+            "{{\"primary\": {}, \"secondary\": {}, \"points\": [{}], \"hill_radius\": {}, \"hill_radii\": [{}]}}",
+            self.primary,
+            self.secondary,
+            points.join(", "),
+            self.hill_radius,
+            hill_radii.join(", ")
+        )
+    }
+}
+
+///Solves the equilibrium on the line through both bodies by Newton's method. Lengths are in units of the
+///separation with the barycentre in the origin, so the primary sits at `-mu` and the secondary at `1 - mu`.
+fn collinear(mu: f64, guess: f64) -> f64 {
+    let mut x = guess;
+    for _ in 0..100 {
+        let (r1, r2) = (x + mu, x - 1.0 + mu);
+        let force = x - (1.0 - mu) * r1 / r1.abs().powi(3) - mu * r2 / r2.abs().powi(3);
+        let slope = 1.0 + 2.0 * (1.0 - mu) / r1.abs().powi(3) + 2.0 * mu / r2.abs().powi(3);
+        let delta = force / slope;
+        x -= delta;
+        if delta.abs() < 1e-15 {
+            break;
+        }
+    }
+    x
+}
+
+///Radius of the Hill sphere of `body` on its orbit around `primary`. Uses the periapsis of bound orbits and the
+///current distance otherwise.
+pub(crate) fn hill_radius(body: &Body, primary: &Body, interaction_constant: f64) -> f64 {
+    let r = body.pos - primary.pos;
+    let elements = OrbitalElements::from_state(
+        &r,
+        &(body.vel - primary.vel),
+        interaction_constant * (body.mass + primary.mass),
+    );
+    let distance = if elements.eccentricity < 1.0 {
+        elements.semi_major_axis * (1.0 - elements.eccentricity)
+    } else {
+        r.norm()
+    };
+    distance * (body.mass / (3.0 * primary.mass)).cbrt()
+}
+
+impl LagrangePoints {
+    ///The Lagrange points of the pair of `bodies` at the indices `primary` and `secondary`, together with the
+    ///Hill spheres of all bodies
+    ///
+    /// # Error
+    ///
+    /// Fails if an index is out of range, both are the same, one is a test particle or both share a position.
+    fn of(
+        bodies: &[Body],
+        primary: usize,
+        secondary: usize,
+        interaction_constant: f64,
+    ) -> Res<LagrangePoints> {
+        let (Some(first), Some(second)) = (bodies.get(primary), bodies.get(secondary)) else {
+            return Err(format!(
+                "Invalid pair. There are only {} bodies, but found {primary} and {secondary}.",
+                bodies.len()
+            )
+            .into());
+        };
+        if primary == secondary || first.is_particle() || second.is_particle() {
+            return Err(format!(
+                "Invalid pair. Expected two different massive bodies, but found {primary} and {secondary}."
+            )
+            .into());
+        }
+        let separation = second.pos - first.pos;
+        let distance = separation.norm();
+        if distance == 0.0 {
+            return Err("Invalid pair. Both bodies share the same position.".into());
+        }
+        let mu = second.mass / (first.mass + second.mass);
+        let barycentre = first.pos + separation * mu;
+        //Axes of the frame rotating with the pair. Without relative motion any perpendicular will do
+        let x = separation / distance;
+        let normal = separation.cross(&(second.vel - first.vel));
+        let normal = if normal.norm() > 0.0 {
+            normal.normalize()
+        } else if x.z.abs() < 0.9 {
+            x.cross(&Vector3::z()).normalize()
+        } else {
+            x.cross(&Vector3::x()).normalize()
+        };
+        let y = normal.cross(&x);
+        let hill = (mu / 3.0).cbrt();
+        let in_frame = |u: f64, v: f64| barycentre + (x * u + y * v) * distance;
+        Ok(LagrangePoints {
+            primary,
+            secondary,
+            points: [
+                in_frame(collinear(mu, 1.0 - mu - hill), 0.0),
+                in_frame(collinear(mu, 1.0 - mu + hill), 0.0),
+                in_frame(collinear(mu, -1.0 - 5.0 * mu / 12.0), 0.0),
+                in_frame(0.5 - mu, 0.75f64.sqrt()),
+                in_frame(0.5 - mu, -(0.75f64.sqrt())),
+            ],
+            hill_radius: hill_radius(second, first, interaction_constant),
+            hill_radii: Hierarchy::detect(bodies, interaction_constant)
+                .orbits
+                .iter()
+                .enumerate()
+                .map(|(i, orbit)| {
+                    orbit.map(|o| hill_radius(&bodies[i], &bodies[o.parent], interaction_constant))
+                })
+                .collect(),
+        })
+    }
+}
+
+impl SimState {
+    ///The Lagrange points of the bodies at `primary` and `secondary` in the frame of the simulation
+    ///
+    /// # Error
+    ///
+    /// Fails if an index is out of range, both are the same, one is a test particle or both share a position.
+    pub fn lagrange_points(&self, primary: usize, secondary: usize) -> Res<LagrangePoints> {
+        LagrangePoints::of(
+            &self.bodies,
+            primary,
+            secondary,
+            self.metadata.interaction_constant,
+        )
+    }
+
+    ///Radius of the Hill sphere of the body at `body` on its orbit around the one at `primary`. Inside of it the
+    ///body holds on to its moons against the tides of the primary.
+    pub fn hill_radius(&self, body: usize, primary: usize) -> f64 {
+        hill_radius(
+            &self.bodies[body],
+            &self.bodies[primary],
+            self.metadata.interaction_constant,
+        )
+    }
+}
+
+impl Snapshot {
+    ///The Lagrange points of the bodies at `primary` and `secondary` in the output frame, as seen by the client
+    ///
+    /// # Error
+    ///
+    /// Fails if an index is out of range, both are the same or both share a position.
+    pub fn lagrange_points(&self, primary: usize, secondary: usize) -> Res<LagrangePoints> {
+        LagrangePoints::of(
+            &self.bodies,
+            primary,
+            secondary,
+            self.metadata.interaction_constant,
+        )
+    }
+}
@@ -1,14 +1,62 @@
 extern crate nalgebra as na;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 use std::{f64::consts::PI, fmt::Debug};
 
-use na::Rotation3;
 use na::Vector3;
+use rayon::prelude::*;
+
+mod diagnostics;
+mod encounters;
+mod ephemeris;
+mod forces;
+mod generators;
+mod hierarchy;
+mod horizons;
+mod lagrange;
+mod octree;
+mod orbit;
+mod precession;
+mod presets;
+mod spacecraft;
+pub use diagnostics::Diagnostics;
+use encounters::Approach;
+pub use ephemeris::{calendar_date, julian_date, planetary_moons, solar_system};
+pub use forces::{AtmosphericDrag, ConstantField, ForceModel, Law, PairwiseLaw, RadiationPressure};
+pub use generators::{disc_galaxy, plummer, protoplanetary_disc, Generator};
+pub use hierarchy::{Hierarchy, Orbit};
+pub use horizons::{physical_properties, HorizonsRecord};
+pub use lagrange::LagrangePoints;
+use octree::Octree;
+pub use orbit::OrbitalElements;
+pub use precession::{mercury_precession, Precession};
+pub use presets::{preset, Preset, PRESETS};
+pub use spacecraft::Manoeuvre;
+use spacecraft::Spacecraft;
 
 pub type Res<T> = Result<T, Box<dyn std::error::Error>>;
 
 const AU: f64 = 149597870700.0;
+///Mass of the sun in kg
+const SOLAR_MASS: f64 = 1.98847e30;
+///Newtonian constant of gravitation in m³/(kg s²)
+pub const GRAVITATIONAL_CONSTANT: f64 = 6.67430e-11;
+///Speed of light in m/s
+const C: f64 = 299792458.0;
+///Default of the `tolerance` of the metadata
+const TOLERANCE: f64 = 1e-9;
+///Default of the `analysis_interval` of the metadata, so every frame is analysed unless the client throttles it
+const ANALYSIS_INTERVAL: usize = 1;
+///Default of the `max_acceleration` of the metadata in m/s²
 const EXTREME_ACC: f64 = 1e6;
+///Upper limit of the sub-steps of `AccelerationPolicy::ReduceStep`, so a singular acceleration cannot stall the
+///simulation
+const MAX_REDUCTION: usize = 1000;
+///Roche limit of a fluid body in radii of the primary, for equal densities
+const ROCHE_FACTOR: f64 = 2.44;
+///Below this many bodies per thread, handing the force calculation to the worker pool costs more than it saves
+const MIN_BODIES_PER_THREAD: usize = 64;
 
 #[derive(Debug)]
 ///Categorises the Inputs from the client
@@ -17,6 +65,192 @@ pub enum InputEvent {
     Remove(usize),
     Update(usize),
     Meta,
+    ///Shift all bodies into the barycentric frame
+    Recenter,
+    ///Replace the simulation by the preset with the given name
+    Preset(String),
+    ///Append the manoeuvre to the schedule of the spacecraft at the index
+    Manoeuvre(usize, Manoeuvre),
+}
+
+///Changes the simulation made on its own, that the client has to be notified about
+#[derive(Debug)]
+pub enum SimEvent {
+    ///The body at `index` was removed
+    Removed(usize),
+    ///The body at `removed` collided with the one at `survivor` and was merged into it
+    Collision {
+        survivor: usize,
+        removed: usize,
+        mass: f64,
+        radius: f64,
+    },
+    ///The bodies at `first` and `second` bounced off each other, exchanging the momentum `impulse`
+    Bounce {
+        first: usize,
+        second: usize,
+        impulse: f64,
+    },
+    ///The bodies at `first` and `second` collided and broke into `fragments` pieces. Two of them replace
+    ///the colliding bodies, the others are appended starting at `first_new`
+    Fragmentation {
+        first: usize,
+        second: usize,
+        first_new: usize,
+        fragments: usize,
+    },
+    ///The body at `index` came closer to the one at `primary` than its Roche limit and broke into `fragments`
+    ///pieces. One of them replaces the body, the others are inserted starting at `first_new`
+    Disruption {
+        index: usize,
+        primary: usize,
+        first_new: usize,
+        fragments: usize,
+    },
+    ///The simulation was replaced by the preset `name` with `bodies` massive bodies
+    Loaded { name: &'static str, bodies: usize },
+    ///The body at `index` exceeded the `max_acceleration` of the metadata with `acceleration` and was handled
+    ///according to `policy`
+    ExtremeAcceleration {
+        index: usize,
+        acceleration: f64,
+        policy: AccelerationPolicy,
+    },
+    ///The spacecraft at `index` completed a manoeuvre. `remaining` are still scheduled
+    Manoeuvre {
+        index: usize,
+        remaining: usize,
+        propellant: f64,
+    },
+    ///The bodies at `first` and `second` came closer than the encounter distance of the metadata. They were
+    ///closest at the simulated `time` with the `distance` and relative `speed`
+    Encounter {
+        first: usize,
+        second: usize,
+        time: f64,
+        distance: f64,
+        speed: f64,
+    },
+    ///The body at `index` is unbound and left the escape radius of the metadata around the barycentre of the
+    ///others at the simulated `time`, with the `distance` and relative `speed`
+    Escape {
+        index: usize,
+        time: f64,
+        distance: f64,
+        speed: f64,
+    },
+    ///The body the metadata parameter `parameter` referred to is gone, so the parameter was reset to 0
+    Reset { parameter: String },
+}
+
+impl Display for SimEvent {
+    ///The data of the event as JSON
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SimEvent::Removed(index) => write!(f, "{{\"index\": {}}}", index),
+            SimEvent::Collision {
+                survivor,
+                removed,
+                mass,
+                radius,
+            } => write!(
+                f,
+                "{{\"survivor\": {}, \"removed\": {}, \"mass\": {}, \"radius\": {}}}",
+                survivor, removed, mass, radius
+            ),
+            SimEvent::Bounce {
+                first,
+                second,
+                impulse,
+            } => write!(
+                f,
+                "{{\"first\": {}, \"second\": {}, \"impulse\": {}}}",
+                first, second, impulse
+            ),
+            SimEvent::Fragmentation {
+                first,
+                second,
+                first_new,
+                fragments,
+            } => write!(
+                f,
+                "{{\"first\": {}, \"second\": {}, \"first_new\": {}, \"fragments\": {}}}",
+                first, second, first_new, fragments
+            ),
+            SimEvent::Disruption {
+                index,
+                primary,
+                first_new,
+                fragments,
+            } => write!(
+                f,
+                "{{\"index\": {}, \"primary\": {}, \"first_new\": {}, \"fragments\": {}}}",
+                index, primary, first_new, fragments
+            ),
+            SimEvent::Loaded { name, bodies } => {
+                write!(f, "{{\"name\": \"{}\", \"bodies\": {}}}", name, bodies)
+            }
+            SimEvent::Manoeuvre {
+                index,
+                remaining,
+                propellant,
+            } => write!(
+                f,
+                "{{\"index\": {}, \"remaining\": {}, \"propellant\": {}}}",
+                index, remaining, propellant
+            ),
+            SimEvent::ExtremeAcceleration {
+                index,
+                acceleration,
+                policy,
+            } => write!(
+                f,
+                "{{\"index\": {}, \"acceleration\": {}, \"policy\": \"{}\"}}",
+                index, acceleration, policy
+            ),
+            SimEvent::Encounter {
+                first,
+                second,
+                time,
+                distance,
+                speed,
+            } => write!(
+                f,
+                "{{\"first\": {}, \"second\": {}, \"time\": {}, \"distance\": {}, \"speed\": {}}}",
+                first, second, time, distance, speed
+            ),
+            SimEvent::Escape {
+                index,
+                time,
+                distance,
+                speed,
+            } => write!(
+                f,
+                "{{\"index\": {}, \"time\": {}, \"distance\": {}, \"speed\": {}}}",
+                index, time, distance, speed
+            ),
+            SimEvent::Reset { parameter } => write!(f, "{{\"parameter\": \"{}\"}}", parameter),
+        }
+    }
+}
+
+impl SimEvent {
+    ///The name of the event in the event stream
+    pub fn name(&self) -> &'static str {
+        match self {
+            SimEvent::Removed(_) => "removed",
+            SimEvent::Collision { .. } => "collision",
+            SimEvent::Bounce { .. } => "bounce",
+            SimEvent::Fragmentation { .. } => "fragmentation",
+            SimEvent::Disruption { .. } => "disruption",
+            SimEvent::Loaded { .. } => "loaded",
+            SimEvent::Manoeuvre { .. } => "manoeuvre",
+            SimEvent::ExtremeAcceleration { .. } => "extreme_acceleration",
+            SimEvent::Encounter { .. } => "encounter",
+            SimEvent::Escape { .. } => "escape",
+            SimEvent::Reset { .. } => "reset",
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -27,6 +261,12 @@ pub struct Body {
     pos: Vector3<f64>,
     vel: Vector3<f64>,
     acc: Vector3<f64>,
+    ///Test particles feel the gravitation of the massive bodies, but do not attract anything themselves
+    particle: bool,
+    ///Fragments of a tidal disruption. They are loose rubble, so they are not disrupted again
+    debris: bool,
+    ///Dry mass and manoeuvre schedule, if the body is a spacecraft
+    spacecraft: Option<Spacecraft>,
 }
 
 impl Debug for Body {
@@ -41,15 +281,53 @@ impl Body {
         Body {
             mass,
             density,
-            radius: Body::radius(mass, density),
+            radius: Body::radius_of(mass, density),
+            pos: Vector3::zeros(),
+            vel: Vector3::zeros(),
+            acc: Vector3::zeros(),
+            particle: false,
+            debris: false,
+            spacecraft: None,
+        }
+    }
+
+    ///A massless test particle. Place it with `set_position` and `set_velocity`.
+    pub fn particle() -> Body {
+        Body {
+            mass: 0.0,
+            density: 0.0,
+            radius: 0.0,
             pos: Vector3::zeros(),
             vel: Vector3::zeros(),
             acc: Vector3::zeros(),
+            particle: true,
+            debris: false,
+            spacecraft: None,
         }
     }
 
+    pub fn is_particle(&self) -> bool {
+        self.particle
+    }
+
+    pub fn mass(&self) -> f64 {
+        self.mass
+    }
+
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    pub fn pos(&self) -> Vector3<f64> {
+        self.pos
+    }
+
+    pub fn vel(&self) -> Vector3<f64> {
+        self.vel
+    }
+
     pub fn sun() -> Body {
-        Body::new(1.98847e30, 1410.0)
+        Body::new(SOLAR_MASS, 1410.0)
     }
 
     pub fn earth() -> Body {
@@ -131,6 +409,13 @@ impl Body {
                 "vx" => body.vel.x = v.parse()?,
                 "vy" => body.vel.y = v.parse()?,
                 "vz" => body.vel.z = v.parse()?,
+                "particle" => body.particle = v.trim().parse()?,
+                "dry_mass" => {
+                    body.spacecraft = Some(Spacecraft {
+                        dry_mass: v.trim().parse()?,
+                        schedule: vec![],
+                    })
+                }
                 _ => {
                     return Err(format!(
                         "Invalid string. The parameter '{}' does not exist on type 'Body'.",
@@ -139,23 +424,94 @@ impl Body {
                     .into())
                 }
             }
-            body.radius = Body::radius(body.mass, body.density);
+            body.radius = Body::radius_of(body.mass, body.density);
+        }
+        if body.particle {
+            body = Body::particle()
+                .set_position(body.pos)
+                .set_velocity(body.vel);
         }
         Ok(body)
     }
 
-    pub fn radius(mass: f64, density: f64) -> f64 {
+    pub fn radius_of(mass: f64, density: f64) -> f64 {
         (0.75 * mass / (PI * density)).cbrt()
     }
 
-    pub fn mass(density: f64, radius: f64) -> f64 {
+    pub fn mass_of(density: f64, radius: f64) -> f64 {
         1.25 * PI * radius.powi(3) * density
     }
 
-    pub fn density(mass: f64, radius: f64) -> f64 {
+    pub fn density_of(mass: f64, radius: f64) -> f64 {
         mass / (0.75 * PI * radius.powi(3))
     }
 
+    ///Whether the spheres of both bodies overlap
+    fn overlaps(&self, other: &Body) -> bool {
+        let reach = self.radius + other.radius;
+        reach.is_finite() && (self.pos - other.pos).norm() < reach
+    }
+
+    ///Breaks the body into `count` equal fragments on a ring perpendicular to `normal`. The fragments are
+    ///spaced so they do not touch and fly apart with the escape velocity of the whole body, so the total
+    ///momentum is conserved. The fragments of a spacecraft are wreckage and perform none of its manoeuvres.
+    fn fragment(
+        &self,
+        count: usize,
+        normal: &Vector3<f64>,
+        interaction_constant: f64,
+    ) -> Vec<Body> {
+        let normal = if normal.norm() > 0.0 {
+            normal.normalize()
+        } else {
+            Vector3::z()
+        };
+        let helper = if normal.x.abs() < 0.9 {
+            Vector3::x()
+        } else {
+            Vector3::y()
+        };
+        let u = normal.cross(&helper).normalize();
+        let w = normal.cross(&u);
+        let mass = self.mass / count as f64;
+        let fragment_radius = Body::radius_of(mass, self.density);
+        let ring_radius = self
+            .radius
+            .max(1.1 * fragment_radius / (PI / count as f64).sin());
+        let speed = (2.0 * interaction_constant * self.mass / ring_radius).sqrt();
+        (0..count)
+            .map(|n| {
+                let angle = 2.0 * PI * n as f64 / count as f64;
+                let direction = u * angle.cos() + w * angle.sin();
+                let mut fragment = Body::new(mass, self.density)
+                    .set_position(self.pos + direction * ring_radius)
+                    .set_velocity(self.vel + direction * speed);
+                fragment.acc = self.acc;
+                fragment
+            })
+            .collect()
+    }
+
+    ///Distance to `primary` below which its tides tear the body apart
+    pub fn roche_limit(&self, primary: &Body) -> f64 {
+        ROCHE_FACTOR * primary.radius * (primary.density / self.density).cbrt()
+    }
+
+    ///Combines both bodies into one, conserving mass, momentum and volume. The new body sits in the
+    ///center of mass of both. A spacecraft docking with a body of similar mass stays one and keeps its
+    ///propellant and manoeuvres, see `Spacecraft::merge`.
+    fn merge(&self, other: &Body) -> Body {
+        let mass = self.mass + other.mass;
+        let volume = self.mass / self.density + other.mass / other.density;
+        let mut merged = Body::new(mass, mass / volume);
+        merged.pos = (self.pos * self.mass + other.pos * other.mass) / mass;
+        merged.vel = (self.vel * self.mass + other.vel * other.mass) / mass;
+        merged.acc = (self.acc * self.mass + other.acc * other.mass) / mass;
+        merged.debris = self.debris && other.debris;
+        merged.spacecraft = Spacecraft::merge(self, other);
+        merged
+    }
+
     pub fn set_position(mut self, pos: Vector3<f64>) -> Body {
         self.pos = pos;
         self
@@ -176,14 +532,9 @@ impl Body {
         self.pos += self.vel * time_delta
     }
 
-    ///Changes the speed of the body according to its acceleration. Resets the acceleration to 0
-    fn accelerate(&mut self, time_delta: f64) -> Res<()> {
-        if self.acc.norm() > EXTREME_ACC {
-            return Err("Extreme acceleration".into());
-        }
+    ///Changes the speed of the body according to its acceleration
+    fn accelerate(&mut self, time_delta: f64) {
         self.vel += self.acc * time_delta;
-        self.acc = Vector3::zeros();
-        Ok(())
     }
 
     // /Performs one time step consisting of acceleration and movement
@@ -193,28 +544,822 @@ impl Body {
     // }
 }
 
+///Summary of how an `Integrator` covered the requested time step
+#[derive(Clone, Copy)]
+pub struct StepReport {
+    pub substeps: usize,
+    ///The largest estimated local error of the sub-steps
+    pub error: f64,
+}
+
+impl Display for StepReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{\"substeps\": {}, \"error\": {}}}",
+            self.substeps, self.error
+        )
+    }
+}
+
+impl StepReport {
+    pub fn new(substeps: usize, error: f64) -> StepReport {
+        StepReport { substeps, error }
+    }
+
+    ///Adds the sub-steps of `other` and keeps the larger error
+    fn add(&mut self, other: StepReport) {
+        self.substeps += other.substeps;
+        self.error = self.error.max(other.error);
+    }
+}
+
+///Advances the bodies of a simulation by one time step
+pub trait Integrator {
+    ///Moves `bodies` forward by `time_delta`. `acceleration` returns the acceleration of every body for a given state.
+    ///
+    ///When called the `acc` of every body holds the acceleration at the start of the step. Afterwards it may
+    ///still hold that one, so callers recompute it before the next step.
+    fn step(
+        &self,
+        bodies: &mut [Body],
+        time_delta: f64,
+        acceleration: &dyn Fn(&[Body]) -> Vec<Vector3<f64>>,
+    ) -> StepReport;
+}
+
+///The number of massive bodies. Test particles always follow all massive bodies.
+fn massive_count(bodies: &[Body]) -> usize {
+    bodies.partition_point(|b| !b.particle)
+}
+
+///Maps the old index of a body to the new one once the body at `removed` is gone. If it merged into the body at
+///`survivor`, which comes before it, that one takes its place.
+fn removal(removed: usize, survivor: Option<usize>) -> impl Fn(usize) -> Option<usize> {
+    move |k| match k.cmp(&removed) {
+        Ordering::Less => Some(k),
+        Ordering::Equal => survivor,
+        Ordering::Greater => Some(k - 1),
+    }
+}
+
+///Maps the old index of a body to the new one once `count` bodies are inserted at `at`
+fn insertion(at: usize, count: usize) -> impl Fn(usize) -> Option<usize> {
+    move |k| Some(if k >= at { k + count } else { k })
+}
+
+///Overwrites the acceleration of every body with `accs`
+fn set_accelerations(bodies: &mut [Body], accs: Vec<Vector3<f64>>) {
+    for (body, acc) in bodies.iter_mut().zip(accs) {
+        body.acc = acc;
+    }
+}
+
+///First order symplectic scheme. Kicks the velocity first and then moves with the new velocity
+pub struct SemiImplicitEuler;
+
+impl Integrator for SemiImplicitEuler {
+    fn step(
+        &self,
+        bodies: &mut [Body],
+        time_delta: f64,
+        _acceleration: &dyn Fn(&[Body]) -> Vec<Vector3<f64>>,
+    ) -> StepReport {
+        for body in bodies.iter_mut() {
+            body.accelerate(time_delta);
+            body.movement(time_delta);
+        }
+        StepReport::new(1, 0.0)
+    }
+}
+
+///Second order symplectic scheme in the kick-drift-kick form, also known as leapfrog
+pub struct Verlet;
+
+impl Verlet {
+    ///One kick-drift-kick. Expects and leaves the acceleration of the current state in `acc`
+    fn kick_drift_kick(
+        bodies: &mut [Body],
+        time_delta: f64,
+        acceleration: &dyn Fn(&[Body]) -> Vec<Vector3<f64>>,
+    ) {
+        for body in bodies.iter_mut() {
+            body.accelerate(0.5 * time_delta);
+            body.movement(time_delta);
+        }
+        set_accelerations(bodies, acceleration(bodies));
+        for body in bodies.iter_mut() {
+            body.accelerate(0.5 * time_delta);
+        }
+    }
+}
+
+impl Integrator for Verlet {
+    fn step(
+        &self,
+        bodies: &mut [Body],
+        time_delta: f64,
+        acceleration: &dyn Fn(&[Body]) -> Vec<Vector3<f64>>,
+    ) -> StepReport {
+        Verlet::kick_drift_kick(bodies, time_delta, acceleration);
+        StepReport::new(1, 0.0)
+    }
+}
+
+///Evaluates the derivative `(velocity, acceleration)` of the state of `bodies` offset by `h` times `(dx, dv)`
+fn derivative(
+    bodies: &[Body],
+    dx: &[Vector3<f64>],
+    dv: &[Vector3<f64>],
+    h: f64,
+    acceleration: &dyn Fn(&[Body]) -> Vec<Vector3<f64>>,
+) -> (Vec<Vector3<f64>>, Vec<Vector3<f64>>) {
+    let mut trial = bodies.to_vec();
+    for (i, body) in trial.iter_mut().enumerate() {
+        body.pos += dx[i] * h;
+        body.vel += dv[i] * h;
+    }
+    let vel: Vec<Vector3<f64>> = trial.iter().map(|b| b.vel).collect();
+    (vel, acceleration(&trial))
+}
+
+///Classic fourth order Runge-Kutta. Not symplectic, but very accurate for short runs
+pub struct RungeKutta4;
+
+impl Integrator for RungeKutta4 {
+    fn step(
+        &self,
+        bodies: &mut [Body],
+        time_delta: f64,
+        acceleration: &dyn Fn(&[Body]) -> Vec<Vector3<f64>>,
+    ) -> StepReport {
+        let k1x: Vec<Vector3<f64>> = bodies.iter().map(|b| b.vel).collect();
+        let k1v: Vec<Vector3<f64>> = bodies.iter().map(|b| b.acc).collect();
+        let (k2x, k2v) = derivative(bodies, &k1x, &k1v, 0.5 * time_delta, acceleration);
+        let (k3x, k3v) = derivative(bodies, &k2x, &k2v, 0.5 * time_delta, acceleration);
+        let (k4x, k4v) = derivative(bodies, &k3x, &k3v, time_delta, acceleration);
+        for (i, body) in bodies.iter_mut().enumerate() {
+            body.pos += (k1x[i] + 2.0 * k2x[i] + 2.0 * k3x[i] + k4x[i]) * time_delta / 6.0;
+            body.vel += (k1v[i] + 2.0 * k2v[i] + 2.0 * k3v[i] + k4v[i]) * time_delta / 6.0;
+        }
+        StepReport::new(1, 0.0)
+    }
+}
+
+///Fourth order symplectic scheme composed of three leapfrog steps with Yoshida's coefficients
+pub struct Yoshida4;
+
+impl Integrator for Yoshida4 {
+    fn step(
+        &self,
+        bodies: &mut [Body],
+        time_delta: f64,
+        acceleration: &dyn Fn(&[Body]) -> Vec<Vector3<f64>>,
+    ) -> StepReport {
+        let cbrt2 = 2.0_f64.cbrt();
+        let w1 = 1.0 / (2.0 - cbrt2);
+        let w0 = -cbrt2 / (2.0 - cbrt2);
+        for w in [w1, w0, w1] {
+            Verlet::kick_drift_kick(bodies, w * time_delta, acceleration);
+        }
+        StepReport::new(1, 0.0)
+    }
+}
+
+///Maximum number of sub-steps `DormandPrince` takes for one time step. Once reached the rest of the
+///interval is covered in one step, regardless of the error.
+const MAX_SUBSTEPS: usize = 10000;
+
+///Adaptive Runge-Kutta scheme with embedded error control, using the fifth order Dormand-Prince pair.
+///
+///The requested time step is split into sub-steps, so that the estimated local error of each one stays below
+///`tolerance`. The error is measured relative to the largest position and velocity in the system.
+pub struct DormandPrince {
+    pub tolerance: f64,
+}
+
+impl DormandPrince {
+    const A: [[f64; 6]; 7] = [
+        [0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+        [0.2, 0.0, 0.0, 0.0, 0.0, 0.0],
+        [3.0 / 40.0, 9.0 / 40.0, 0.0, 0.0, 0.0, 0.0],
+        [44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0, 0.0, 0.0, 0.0],
+        [
+            19372.0 / 6561.0,
+            -25360.0 / 2187.0,
+            64448.0 / 6561.0,
+            -212.0 / 729.0,
+            0.0,
+            0.0,
+        ],
+        [
+            9017.0 / 3168.0,
+            -355.0 / 33.0,
+            46732.0 / 5247.0,
+            49.0 / 176.0,
+            -5103.0 / 18656.0,
+            0.0,
+        ],
+        [
+            35.0 / 384.0,
+            0.0,
+            500.0 / 1113.0,
+            125.0 / 192.0,
+            -2187.0 / 6784.0,
+            11.0 / 84.0,
+        ],
+    ];
+    ///Difference between the fifth and the embedded fourth order weights
+    const E: [f64; 7] = [
+        71.0 / 57600.0,
+        0.0,
+        -71.0 / 16695.0,
+        71.0 / 1920.0,
+        -17253.0 / 339200.0,
+        22.0 / 525.0,
+        -1.0 / 40.0,
+    ];
+
+    ///Tries a single sub-step of length `h`. Returns the new state, including the acceleration at the new state,
+    ///and the estimated error relative to `tolerance`.
+    fn try_step(
+        &self,
+        bodies: &[Body],
+        h: f64,
+        acceleration: &dyn Fn(&[Body]) -> Vec<Vector3<f64>>,
+    ) -> (Vec<Body>, f64) {
+        let n = bodies.len();
+        let mut kx: Vec<Vec<Vector3<f64>>> = vec![bodies.iter().map(|b| b.vel).collect()];
+        let mut kv: Vec<Vec<Vector3<f64>>> = vec![bodies.iter().map(|b| b.acc).collect()];
+        for (s, a) in DormandPrince::A.iter().enumerate().skip(1) {
+            let mut dx = vec![Vector3::zeros(); n];
+            let mut dv = vec![Vector3::zeros(); n];
+            for (j, a_sj) in a.iter().enumerate().take(s) {
+                for i in 0..n {
+                    dx[i] += kx[j][i] * *a_sj;
+                    dv[i] += kv[j][i] * *a_sj;
+                }
+            }
+            let (x, v) = derivative(bodies, &dx, &dv, h, acceleration);
+            kx.push(x);
+            kv.push(v);
+        }
+        //The last stage is evaluated at the fifth order solution, so its acceleration can be reused
+        let mut new_bodies = bodies.to_vec();
+        let mut max_pos: f64 = 0.0;
+        let mut max_vel: f64 = 0.0;
+        let mut err_pos: f64 = 0.0;
+        let mut err_vel: f64 = 0.0;
+        for (i, body) in new_bodies.iter_mut().enumerate() {
+            let mut ex = Vector3::zeros();
+            let mut ev = Vector3::zeros();
+            for (j, a_7j) in DormandPrince::A[6].iter().enumerate() {
+                body.pos += kx[j][i] * *a_7j * h;
+                body.vel += kv[j][i] * *a_7j * h;
+            }
+            for (j, e_j) in DormandPrince::E.iter().enumerate() {
+                ex += kx[j][i] * *e_j * h;
+                ev += kv[j][i] * *e_j * h;
+            }
+            body.acc = kv[6][i];
+            max_pos = max_pos.max(body.pos.norm());
+            max_vel = max_vel.max(body.vel.norm());
+            err_pos = err_pos.max(ex.norm());
+            err_vel = err_vel.max(ev.norm());
+        }
+        let error = (err_pos / (self.tolerance * max_pos).max(f64::MIN_POSITIVE))
+            .max(err_vel / (self.tolerance * max_vel).max(f64::MIN_POSITIVE));
+        (new_bodies, error)
+    }
+}
+
+impl Integrator for DormandPrince {
+    fn step(
+        &self,
+        bodies: &mut [Body],
+        time_delta: f64,
+        acceleration: &dyn Fn(&[Body]) -> Vec<Vector3<f64>>,
+    ) -> StepReport {
+        let mut report = StepReport::new(0, 0.0);
+        let mut remaining = time_delta;
+        let mut h = time_delta;
+        while remaining.abs() > 0.0 {
+            if h.abs() > remaining.abs() || report.substeps + 1 >= MAX_SUBSTEPS {
+                h = remaining;
+            }
+            let (new_bodies, error) = self.try_step(bodies, h, acceleration);
+            let accept = error <= 1.0 || report.substeps + 1 >= MAX_SUBSTEPS;
+            if accept {
+                bodies.clone_from_slice(&new_bodies);
+                remaining -= h;
+                report.add(StepReport::new(1, error * self.tolerance));
+            }
+            //Standard step size control with a safety factor, limiting the change to a factor of 5
+            let factor = if error > 0.0 {
+                (0.9 * error.powf(-0.2)).clamp(0.2, 5.0)
+            } else {
+                5.0
+            };
+            h *= factor;
+        }
+        report
+    }
+}
+
+///Selects the `Integrator` used by a simulation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntegratorKind {
+    SemiImplicitEuler,
+    Verlet,
+    RungeKutta4,
+    Yoshida4,
+    DormandPrince,
+}
+
+impl Display for IntegratorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            IntegratorKind::SemiImplicitEuler => "euler",
+            IntegratorKind::Verlet => "verlet",
+            IntegratorKind::RungeKutta4 => "rk4",
+            IntegratorKind::Yoshida4 => "yoshida",
+            IntegratorKind::DormandPrince => "dopri",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl IntegratorKind {
+    pub fn from_string(kind_str: &str) -> Res<IntegratorKind> {
+        match kind_str.trim().trim_matches('"') {
+            "euler" => Ok(IntegratorKind::SemiImplicitEuler),
+            "verlet" | "leapfrog" => Ok(IntegratorKind::Verlet),
+            "rk4" => Ok(IntegratorKind::RungeKutta4),
+            "yoshida" => Ok(IntegratorKind::Yoshida4),
+            "dopri" => Ok(IntegratorKind::DormandPrince),
+            x => Err(format!(
+                "Invalid integrator. Expected 'euler', 'verlet', 'rk4', 'yoshida' or 'dopri', but found {x}."
+            )
+            .into()),
+        }
+    }
+}
+
+///Decides how the simulated time advances compared to the real time
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pacing {
+    ///Every step covers the real time since the last one, scaled by `time_scaling`. Not reproducible
+    Wall,
+    ///Steps of constant `fixed_dt`, as many as needed to follow the real time scaled by `time_scaling`
+    RealTime,
+    ///Steps of constant `fixed_dt`, one after another without waiting
+    AsFastAsPossible,
+    ///`steps_per_frame` steps of constant `fixed_dt` for every frame send to the client
+    FrameLocked,
+}
+
+impl Display for Pacing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Pacing::Wall => "wall",
+            Pacing::RealTime => "realtime",
+            Pacing::AsFastAsPossible => "fast",
+            Pacing::FrameLocked => "frame",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Pacing {
+    pub fn from_string(pacing_str: &str) -> Res<Pacing> {
+        match pacing_str.trim().trim_matches('"') {
+            "wall" => Ok(Pacing::Wall),
+            "realtime" => Ok(Pacing::RealTime),
+            "fast" => Ok(Pacing::AsFastAsPossible),
+            "frame" => Ok(Pacing::FrameLocked),
+            x => Err(format!(
+                "Invalid pacing. Expected 'wall', 'realtime', 'fast' or 'frame', but found {x}."
+            )
+            .into()),
+        }
+    }
+}
+
+///Maximum number of fixed steps `Pacer` hands out at once. If the simulation cannot keep up with the real
+///time, the missing time is dropped instead of piling up.
+const MAX_STEPS_PER_CALL: usize = 1000;
+
+///Translates the passing real time into a number of fixed steps, according to the `Pacing` of the metadata
+pub struct Pacer {
+    frame_time_s: f64,
+    backlog: f64,
+    since_frame: f64,
+}
+
+impl Pacer {
+    ///`frame_time_s` is the real time between two frames, used by `Pacing::FrameLocked`
+    pub fn new(frame_time_s: f64) -> Pacer {
+        Pacer {
+            frame_time_s,
+            backlog: 0.0,
+            since_frame: 0.0,
+        }
+    }
+
+    ///The number of steps of `fixed_dt` to perform now, given `real_elapsed` seconds since the last call
+    pub fn steps_due(&mut self, meta: &SimMetaData, real_elapsed: f64) -> usize {
+        if meta.time_scaling == 0.0 || meta.fixed_dt <= 0.0 {
+            self.backlog = 0.0;
+            return 0;
+        }
+        match meta.pacing {
+            Pacing::Wall => 0,
+            Pacing::RealTime => {
+                self.backlog += real_elapsed * meta.time_scaling;
+                let steps = (self.backlog / meta.fixed_dt).floor() as usize;
+                self.backlog -= steps as f64 * meta.fixed_dt;
+                if steps > MAX_STEPS_PER_CALL {
+                    self.backlog = 0.0;
+                    return MAX_STEPS_PER_CALL;
+                }
+                steps
+            }
+            Pacing::AsFastAsPossible => 1,
+            Pacing::FrameLocked => {
+                self.since_frame += real_elapsed;
+                if self.since_frame < self.frame_time_s {
+                    return 0;
+                }
+                self.since_frame = 0.0;
+                meta.steps_per_frame
+            }
+        }
+    }
+}
+
+///Selects how the gravitation between the bodies is calculated
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ForceSolver {
+    ///Exact sum over all pairs, O(N²)
+    Direct,
+    ///Barnes-Hut octree approximation with the `opening_angle` of the metadata, O(N log N)
+    BarnesHut,
+}
+
+impl Display for ForceSolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ForceSolver::Direct => "direct",
+            ForceSolver::BarnesHut => "barneshut",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl ForceSolver {
+    pub fn from_string(solver_str: &str) -> Res<ForceSolver> {
+        match solver_str.trim().trim_matches('"') {
+            "direct" => Ok(ForceSolver::Direct),
+            "barneshut" => Ok(ForceSolver::BarnesHut),
+            x => Err(
+                format!("Invalid solver. Expected 'direct' or 'barneshut', but found {x}.").into(),
+            ),
+        }
+    }
+}
+
+///Decides what happens when the spheres of two bodies touch
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CollisionPolicy {
+    ///Both bodies become one, conserving mass and momentum
+    Merge,
+    ///The bodies bounce off each other with the `restitution` of the metadata
+    Bounce,
+    ///The bodies break into `fragments` pieces, unless they would be lighter than `min_fragment_mass`
+    Fragment,
+    ///Both bodies are removed
+    Remove,
+}
+
+impl Display for CollisionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            CollisionPolicy::Merge => "merge",
+            CollisionPolicy::Bounce => "bounce",
+            CollisionPolicy::Fragment => "fragment",
+            CollisionPolicy::Remove => "remove",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl CollisionPolicy {
+    pub fn from_string(policy_str: &str) -> Res<CollisionPolicy> {
+        match policy_str.trim().trim_matches('"') {
+            "merge" => Ok(CollisionPolicy::Merge),
+            "bounce" => Ok(CollisionPolicy::Bounce),
+            "fragment" => Ok(CollisionPolicy::Fragment),
+            "remove" => Ok(CollisionPolicy::Remove),
+            x => Err(format!(
+                "Invalid collision policy. Expected 'merge', 'bounce', 'fragment' or 'remove', but found {x}."
+            )
+            .into()),
+        }
+    }
+}
+
+///Decides what happens to a body whose acceleration exceeds the `max_acceleration` of the metadata
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AccelerationPolicy {
+    ///The body is removed
+    Remove,
+    ///The acceleration of every body is limited to `max_acceleration`
+    Clamp,
+    ///The step is split into substeps, so the change of velocity per substep stays below `max_acceleration`
+    ///times the full step
+    ReduceStep,
+    ///The simulation stops until the client changes the bodies or the metadata
+    Pause,
+    ///The body merges with the nearest massive body
+    Merge,
+}
+
+impl Display for AccelerationPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            AccelerationPolicy::Remove => "remove",
+            AccelerationPolicy::Clamp => "clamp",
+            AccelerationPolicy::ReduceStep => "reduce_step",
+            AccelerationPolicy::Pause => "pause",
+            AccelerationPolicy::Merge => "merge",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl AccelerationPolicy {
+    pub fn from_string(policy_str: &str) -> Res<AccelerationPolicy> {
+        match policy_str.trim().trim_matches('"') {
+            "remove" => Ok(AccelerationPolicy::Remove),
+            "clamp" => Ok(AccelerationPolicy::Clamp),
+            "reduce_step" => Ok(AccelerationPolicy::ReduceStep),
+            "pause" => Ok(AccelerationPolicy::Pause),
+            "merge" => Ok(AccelerationPolicy::Merge),
+            x => Err(format!(
+                "Invalid acceleration policy. Expected 'remove', 'clamp', 'reduce_step', 'pause' or 'merge', but found {x}."
+            )
+            .into()),
+        }
+    }
+}
+
+///Smooths the gravitation at small distances, so close bodies do not get infinite accelerations
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Softening {
+    ///Plain inverse square law
+    None,
+    ///Plummer softening. Acts like the distance is increased by the `softening_length`
+    Plummer,
+    ///Cubic spline kernel with the support `softening_length`. Exactly Newtonian beyond it
+    Spline,
+}
+
+impl Display for Softening {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Softening::None => "none",
+            Softening::Plummer => "plummer",
+            Softening::Spline => "spline",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Softening {
+    pub fn from_string(softening_str: &str) -> Res<Softening> {
+        match softening_str.trim().trim_matches('"') {
+            "none" => Ok(Softening::None),
+            "plummer" => Ok(Softening::Plummer),
+            "spline" => Ok(Softening::Spline),
+            x => Err(format!(
+                "Invalid softening. Expected 'none', 'plummer' or 'spline', but found {x}."
+            )
+            .into()),
+        }
+    }
+
+    ///The factor `g` of the acceleration `interaction_constant * mass * g * r` for a separation of `distance`
+    fn factor(&self, distance: f64, length: f64) -> f64 {
+        match self {
+            Softening::Plummer => (distance * distance + length * length).powf(-1.5),
+            //Cubic spline kernel as used in Gadget-2
+            Softening::Spline if distance < length => {
+                let u = distance / length;
+                let h3 = length.powi(3);
+                if u < 0.5 {
+                    (32.0 / 3.0 + u * u * (32.0 * u - 38.4)) / h3
+                } else {
+                    (64.0 / 3.0 - 48.0 * u + 38.4 * u * u
+                        - 32.0 / 3.0 * u.powi(3)
+                        - 1.0 / (15.0 * u.powi(3)))
+                        / h3
+                }
+            }
+            _ => distance.powi(-3),
+        }
+    }
+
+    ///The potential per `interaction_constant` and both masses at a separation of `distance`
+    fn potential(&self, distance: f64, length: f64) -> f64 {
+        match self {
+            Softening::Plummer => -1.0 / (distance * distance + length * length).sqrt(),
+            //Cubic spline kernel as used in Gadget-2
+            Softening::Spline if distance < length => {
+                let u = distance / length;
+                if u < 0.5 {
+                    (-14.0 / 5.0 + u * u * (16.0 / 3.0 + u * u * (-48.0 / 5.0 + 32.0 / 5.0 * u)))
+                        / length
+                } else {
+                    (-16.0 / 5.0
+                        + 1.0 / (15.0 * u)
+                        + u * u * (32.0 / 3.0 + u * (-16.0 + u * (48.0 / 5.0 - 32.0 / 15.0 * u))))
+                        / length
+                }
+            }
+            _ => -1.0 / distance,
+        }
+    }
+}
+
+///The reference frame the positions and velocities are send to the client in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Frame {
+    ///The frame the simulation runs in
+    Inertial,
+    ///Follows the center of mass of all bodies
+    Barycentre,
+    ///Follows the body at the index `frame_body` of the metadata
+    Body,
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Frame::Inertial => "inertial",
+            Frame::Barycentre => "barycentre",
+            Frame::Body => "body",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Frame {
+    pub fn from_string(frame_str: &str) -> Res<Frame> {
+        match frame_str.trim().trim_matches('"') {
+            "inertial" => Ok(Frame::Inertial),
+            "barycentre" => Ok(Frame::Barycentre),
+            "body" => Ok(Frame::Body),
+            x => Err(format!(
+                "Invalid frame. Expected 'inertial', 'barycentre' or 'body', but found {x}."
+            )
+            .into()),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct SimMetaData {
     interaction_constant: f64,
     time_scaling: f64,
+    integrator: IntegratorKind,
+    tolerance: f64,
+    pacing: Pacing,
+    fixed_dt: f64,
+    steps_per_frame: usize,
+    solver: ForceSolver,
+    opening_angle: f64,
+    threads: usize,
+    collision_policy: CollisionPolicy,
+    restitution: f64,
+    fragments: usize,
+    min_fragment_mass: f64,
+    ///Break bodies apart that come closer to a more massive body than their Roche limit
+    tidal_disruption: bool,
+    softening: Softening,
+    softening_length: f64,
+    frame: Frame,
+    frame_body: usize,
+    post_newtonian: bool,
+    ///Pairs of bodies closer than this are reported as encounters. 0 turns the detection off
+    encounter_distance: f64,
+    ///Unbound bodies leaving this distance to the barycentre are reported as escaping. 0 turns the detection off
+    escape_radius: f64,
+    ///Accelerations above this in m/s² are handled according to the `acceleration_policy`
+    max_acceleration: f64,
+    acceleration_policy: AccelerationPolicy,
+    ///The diagnostics and the hierarchy are measured for every this many-th snapshot and repeated in between,
+    ///as they cost O(N²). 0 turns them off
+    analysis_interval: usize,
+    ///Additional forces besides gravitation. Their parameters appear as `"<name>.<parameter>"`
+    forces: Vec<Box<dyn ForceModel>>,
+    ///Simulated seconds since the start
+    time: f64,
+    ///Julian date at the start of the simulation, if the bodies belong to a calendar date
+    epoch: Option<f64>,
 }
 
 impl Display for SimMetaData {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{{\"interaction_constant\": {}, \"time_scaling\": {}}}",
-            self.interaction_constant, self.time_scaling
+            "{{\"interaction_constant\": {}, \"time_scaling\": {}, \"integrator\": \"{}\", \"tolerance\": {}, \"pacing\": \"{}\", \"fixed_dt\": {}, \"steps_per_frame\": {}, \"solver\": \"{}\", \"opening_angle\": {}, \"threads\": {}, \"collision_policy\": \"{}\", \"restitution\": {}, \"fragments\": {}, \"min_fragment_mass\": {}, \"tidal_disruption\": {}, \"softening\": \"{}\", \"softening_length\": {}, \"frame\": \"{}\", \"frame_body\": {}, \"post_newtonian\": {}, \"encounter_distance\": {}, \"escape_radius\": {}, \"max_acceleration\": {}, \"acceleration_policy\": \"{}\", \"analysis_interval\": {}, \"time\": {}, \"epoch\": {}{}}}",
+            self.interaction_constant,
+            self.time_scaling,
+            self.integrator,
+            self.tolerance,
+            self.pacing,
+            self.fixed_dt,
+            self.steps_per_frame,
+            self.solver,
+            self.opening_angle,
+            self.threads,
+            self.collision_policy,
+            self.restitution,
+            self.fragments,
+            self.min_fragment_mass,
+            self.tidal_disruption,
+            self.softening,
+            self.softening_length,
+            self.frame,
+            self.frame_body,
+            self.post_newtonian,
+            self.encounter_distance,
+            self.escape_radius,
+            self.max_acceleration,
+            self.acceleration_policy,
+            self.analysis_interval,
+            self.time,
+            match self.epoch {
+                Some(jd) => format!("\"{}\"", calendar_date(jd)),
+                None => String::from("null"),
+            },
+            self.forces
+                .iter()
+                .flat_map(|force| force
+                    .parameters()
+                    .into_iter()
+                    .map(move |(name, value)| format!(", \"{}.{}\": {}", force.name(), name, value)))
+                .collect::<String>()
         )
     }
 }
 
+impl Default for SimMetaData {
+    ///The settings of the standard simulation, running in real time
+    fn default() -> SimMetaData {
+        SimMetaData {
+            interaction_constant: GRAVITATIONAL_CONSTANT,
+            time_scaling: 1.0,
+            integrator: IntegratorKind::Verlet,
+            tolerance: TOLERANCE,
+            pacing: Pacing::Wall,
+            fixed_dt: 3600.0,
+            steps_per_frame: 1,
+            solver: ForceSolver::Direct,
+            opening_angle: 0.5,
+            threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            collision_policy: CollisionPolicy::Merge,
+            restitution: 1.0,
+            fragments: 8,
+            min_fragment_mass: 1e18,
+            tidal_disruption: false,
+            softening: Softening::None,
+            softening_length: 0.0,
+            frame: Frame::Inertial,
+            frame_body: 0,
+            post_newtonian: false,
+            encounter_distance: 0.0,
+            escape_radius: 0.0,
+            max_acceleration: EXTREME_ACC,
+            acceleration_policy: AccelerationPolicy::Remove,
+            analysis_interval: ANALYSIS_INTERVAL,
+            forces: vec![],
+            time: 0.0,
+            epoch: None,
+        }
+    }
+}
+
 impl SimMetaData {
+    ///Parses the parameters given in `meta_str`. Missing ones keep their `Default`
     pub fn from_string(meta_str: &str) -> Res<SimMetaData> {
-        let mut meta = SimMetaData {
-            interaction_constant: 0.0,
-            time_scaling: 0.0,
-        };
+        let mut meta = SimMetaData::default();
+        meta.update(meta_str)?;
+        Ok(meta)
+    }
+
+    ///Overwrites the parameters given in `meta_str`. Parameters missing in `meta_str` are left untouched
+    pub fn update(&mut self, meta_str: &str) -> Res<()> {
         for kv in meta_str
             .trim()
             .strip_prefix("{")
@@ -224,9 +1369,72 @@ impl SimMetaData {
             .split(",")
         {
             let (k, v) = kv.split_once(":").unwrap();
-            match k.strip_prefix("\"").unwrap().strip_suffix("\"").unwrap() {
-                "interaction_constant" => meta.interaction_constant = v.parse()?,
-                "time_scaling" => meta.time_scaling = v.parse()?,
+            let v = v.trim();
+            match k
+                .trim()
+                .strip_prefix("\"")
+                .unwrap()
+                .strip_suffix("\"")
+                .unwrap()
+            {
+                "interaction_constant" => self.interaction_constant = v.parse()?,
+                "time_scaling" => self.time_scaling = v.parse()?,
+                "integrator" => self.integrator = IntegratorKind::from_string(v)?,
+                "tolerance" => {
+                    self.tolerance = v.parse()?;
+                    if self.tolerance.is_nan() || self.tolerance <= 0.0 {
+                        return Err(format!(
+                            "Invalid tolerance. Expected a positive number, but found {v}."
+                        )
+                        .into());
+                    }
+                }
+                "pacing" => self.pacing = Pacing::from_string(v)?,
+                "fixed_dt" => self.fixed_dt = v.parse()?,
+                "steps_per_frame" => self.steps_per_frame = v.parse()?,
+                "solver" => self.solver = ForceSolver::from_string(v)?,
+                "opening_angle" => self.opening_angle = v.parse()?,
+                "threads" => self.threads = v.parse()?,
+                "collision_policy" => self.collision_policy = CollisionPolicy::from_string(v)?,
+                "restitution" => self.restitution = v.parse()?,
+                "fragments" => self.fragments = v.parse()?,
+                "min_fragment_mass" => self.min_fragment_mass = v.parse()?,
+                "tidal_disruption" => self.tidal_disruption = v.parse()?,
+                "softening" => self.softening = Softening::from_string(v)?,
+                "softening_length" => self.softening_length = v.parse()?,
+                "frame" => self.frame = Frame::from_string(v)?,
+                "frame_body" => self.frame_body = v.parse()?,
+                "post_newtonian" => self.post_newtonian = v.parse()?,
+                "encounter_distance" => self.encounter_distance = v.parse()?,
+                "escape_radius" => self.escape_radius = v.parse()?,
+                "max_acceleration" => {
+                    self.max_acceleration = v.parse()?;
+                    if self.max_acceleration.is_nan() || self.max_acceleration <= 0.0 {
+                        return Err(format!(
+                            "Invalid max_acceleration. Expected a positive number, but found {v}."
+                        )
+                        .into());
+                    }
+                }
+                "acceleration_policy" => {
+                    self.acceleration_policy = AccelerationPolicy::from_string(v)?
+                }
+                "analysis_interval" => self.analysis_interval = v.parse()?,
+                "time" => self.time = v.parse()?,
+                "epoch" => {
+                    self.epoch = match v {
+                        "null" => None,
+                        _ => Some(julian_date(v)?),
+                    }
+                }
+                key if key.contains('.') => {
+                    let (name, parameter) = key.split_once('.').unwrap();
+                    self.forces
+                        .iter_mut()
+                        .find(|force| force.name() == name)
+                        .ok_or(format!("Invalid string. No force '{name}' is registered."))?
+                        .set_parameter(parameter, v.parse()?)?;
+                }
                 _ => {
                     return Err(format!(
                         "Invalid string. The parameter '{}' does not exist on type 'SimMetaData'.",
@@ -236,8 +1444,60 @@ impl SimMetaData {
                 }
             }
         }
-        Ok(meta)
+        Ok(())
+    }
+
+    pub fn pacing(&self) -> Pacing {
+        self.pacing
+    }
+
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
+
+    pub fn frame(&self) -> Frame {
+        self.frame
+    }
+
+    pub fn frame_body(&self) -> usize {
+        self.frame_body
+    }
+
+    pub fn max_acceleration(&self) -> f64 {
+        self.max_acceleration
+    }
+
+    pub fn acceleration_policy(&self) -> AccelerationPolicy {
+        self.acceleration_policy
     }
+
+    ///Builds the `Integrator` selected by `integrator`
+    pub fn integrator(&self) -> Box<dyn Integrator> {
+        match self.integrator {
+            IntegratorKind::SemiImplicitEuler => Box::new(SemiImplicitEuler),
+            IntegratorKind::Verlet => Box::new(Verlet),
+            IntegratorKind::RungeKutta4 => Box::new(RungeKutta4),
+            IntegratorKind::Yoshida4 => Box::new(Yoshida4),
+            IntegratorKind::DormandPrince => Box::new(DormandPrince {
+                tolerance: self.tolerance,
+            }),
+        }
+    }
+}
+
+///Everything the client gets to see of one simulation step
+#[derive(Clone)]
+pub struct Snapshot {
+    ///The massive bodies
+    pub bodies: Vec<Body>,
+    ///Positions of the test particles. The particle at index `n` is the body at index `bodies.len() + n`
+    pub particles: Vec<Vector3<f64>>,
+    pub metadata: SimMetaData,
+    pub report: StepReport,
+    ///The last measured diagnostics, `None` while the `analysis_interval` of the metadata is 0
+    pub diagnostics: Option<Diagnostics>,
+    ///Who orbits whom among `bodies` as last detected, `None` while the `analysis_interval` of the metadata is 0
+    pub hierarchy: Option<Hierarchy>,
 }
 
 ///The Main simulation
@@ -245,6 +1505,22 @@ pub struct SimState {
     pub bodies: Vec<Body>,
     pub metadata: SimMetaData,
     pub target_time_per_step_s: f64,
+    ///How the time steps since the last snapshot were covered by the integrator
+    pub report: StepReport,
+    ///The conserved quantities the drift is measured against. Reset whenever the client changes the system
+    baseline: Option<Diagnostics>,
+    ///Pairs of massive bodies currently within the encounter distance
+    encounters: HashMap<(usize, usize), Approach>,
+    ///Set by `AccelerationPolicy::Pause`. No time passes until the client changes the simulation
+    paused: bool,
+    ///Bodies above the `max_acceleration` of the metadata in the last step
+    extreme: HashSet<usize>,
+    ///Snapshots taken since the last change by the client
+    frames: usize,
+    ///The diagnostics of the last snapshot that measured them
+    diagnostics: Option<Diagnostics>,
+    ///The hierarchy of the last snapshot that detected it
+    hierarchy: Option<Hierarchy>,
 }
 
 impl SimState {
@@ -264,72 +1540,723 @@ impl SimState {
                 Body::neptun(),
             ],
             metadata: SimMetaData {
-                interaction_constant: 6.67430e-11,
                 time_scaling,
+                ..SimMetaData::default()
             },
+            report: StepReport::new(0, 0.0),
+            baseline: None,
+            encounters: HashMap::new(),
+            paused: false,
+            extreme: HashSet::new(),
+            frames: 0,
+            diagnostics: None,
+            hierarchy: None,
             target_time_per_step_s,
         }
     }
 
-    ///Calculate the Force accting between a pair of bodies at position `i` and `k` and the resulting acceleration
-    fn interact(&mut self, i: usize, k: usize) {
-        let m1 = self.bodies[i].mass;
-        let m2 = self.bodies[k].mass;
-        let r = self.bodies[k].pos - self.bodies[i].pos;
-        let a = self.metadata.interaction_constant * r / r.norm().powi(3);
-        self.bodies[i].acc += a * m2;
-        self.bodies[k].acc += -a * m1;
+    ///The acceleration per unit mass of a source at the separation `r`, pointing from the attracted body to the source
+    fn kernel(&self, r: Vector3<f64>) -> Vector3<f64> {
+        let factor = self
+            .metadata
+            .softening
+            .factor(r.norm(), self.metadata.softening_length);
+        self.metadata.interaction_constant * r * factor
+    }
+
+    ///Calculate the acceleration of the body at position `i` caused by the gravitation of all massive others.
+    ///Test particles are skipped as sources, so they cost O(N_massive) each.
+    ///
+    ///The contributions are always summed up in the order of the bodies, so the result does not depend on
+    ///how the bodies are distributed among threads.
+    fn interact(&self, bodies: &[Body], i: usize) -> Vector3<f64> {
+        let mut acc = Vector3::zeros();
+        for (k, other) in bodies[..massive_count(bodies)].iter().enumerate() {
+            if k != i {
+                acc += self.kernel(other.pos - bodies[i].pos) * other.mass;
+            }
+        }
+        acc
+    }
+
+    ///Evaluates `acceleration` for every index below `n`. The indices are split into up to `threads` of the
+    ///metadata contiguous chunks, which are handled by the workers of the thread pool shared by all evaluations.
+    fn per_body<F>(&self, n: usize, acceleration: F) -> Vec<Vector3<f64>>
+    where
+        F: Fn(usize) -> Vector3<f64> + Sync + Send,
+    {
+        let threads = self
+            .metadata
+            .threads
+            .min(n.div_ceil(MIN_BODIES_PER_THREAD))
+            .max(1);
+        if threads == 1 {
+            return (0..n).map(acceleration).collect();
+        }
+        (0..n)
+            .into_par_iter()
+            .with_min_len(n.div_ceil(threads))
+            .map(acceleration)
+            .collect()
     }
 
-    ///Performs a time step by calculating the acceleration on each body and then moving them.
+    ///Calculates the acceleration of every body in `bodies` caused by all the others, using the
+    ///`ForceSolver` selected in the metadata
+    pub fn accelerations(&self, bodies: &[Body]) -> Vec<Vector3<f64>> {
+        let tree = match self.metadata.solver {
+            ForceSolver::Direct => None,
+            ForceSolver::BarnesHut => Some(Octree::new(&bodies[..massive_count(bodies)])),
+        };
+        self.per_body(bodies.len(), |i| {
+            let mut acc = match &tree {
+                None => self.interact(bodies, i),
+                Some(tree) => {
+                    tree.acceleration(bodies, i, self.metadata.opening_angle, &|r| self.kernel(r))
+                }
+            };
+            if self.metadata.post_newtonian {
+                acc += self.post_newtonian(bodies, i);
+            }
+            for force in &self.metadata.forces {
+                acc += force.acceleration(bodies, i);
+            }
+            acc
+        })
+    }
+
+    ///Registers `force`, which then acts on every body besides gravitation. Replaces a registered force of the
+    ///same name.
+    pub fn add_force(&mut self, force: Box<dyn ForceModel>) {
+        self.remove_force(force.name());
+        self.metadata.forces.push(force);
+    }
+
+    ///Removes the force called `name`. Returns whether it was registered
+    pub fn remove_force(&mut self, name: &str) -> bool {
+        let count = self.metadata.forces.len();
+        self.metadata.forces.retain(|force| force.name() != name);
+        count != self.metadata.forces.len()
+    }
+
+    ///The registered forces in the order of registration
+    pub fn forces(&self) -> &[Box<dyn ForceModel>] {
+        &self.metadata.forces
+    }
+
+    ///First post-Newtonian correction to the acceleration of the body at position `i`. Every other body is
+    ///treated as the center of a Schwarzschild field, which causes the relativistic perihelion precession.
+    fn post_newtonian(&self, bodies: &[Body], i: usize) -> Vector3<f64> {
+        let mut acc = Vector3::zeros();
+        for (k, other) in bodies[..massive_count(bodies)].iter().enumerate() {
+            if k == i {
+                continue;
+            }
+            let r = bodies[i].pos - other.pos;
+            let v = bodies[i].vel - other.vel;
+            let distance = r.norm();
+            let gm = self.metadata.interaction_constant * other.mass;
+            acc += gm / (C * C * distance.powi(3))
+                * ((4.0 * gm / distance - v.norm_squared()) * r + 4.0 * r.dot(&v) * v);
+        }
+        acc
+    }
+
+    ///Performs a time step covering the real time `time_delta`, scaled by the `time_scaling` of the metadata.
     pub fn time_step(
         &mut self,
         time_delta: f64,
-        remove_sender: crossbeam_channel::Sender<usize>,
+        event_sender: crossbeam_channel::Sender<SimEvent>,
     ) -> Vec<usize> {
-        for i in 0..self.bodies.len() {
-            for k in i + 1..self.bodies.len() {
-                self.interact(i, k);
-            }
+        self.advance(time_delta * self.metadata.time_scaling, event_sender)
+    }
+
+    ///Performs a time step of the constant `fixed_dt` of the metadata. Independent of the real time, so
+    ///runs with the same initial state and the same number of steps are reproducible.
+    pub fn fixed_step(&mut self, event_sender: crossbeam_channel::Sender<SimEvent>) -> Vec<usize> {
+        self.advance(self.metadata.fixed_dt, event_sender)
+    }
+
+    ///Advances the simulated time by `sim_time_delta` by calculating the acceleration on each body and then
+    ///moving them with the `Integrator` selected in the metadata.
+    ///
+    ///Bodies with an extreme acceleration are handled according to the `AccelerationPolicy` of the metadata
+    ///before moving. Bodies overlapping afterwards are handled according to the `CollisionPolicy`.
+    fn advance(
+        &mut self,
+        sim_time_delta: f64,
+        event_sender: crossbeam_channel::Sender<SimEvent>,
+    ) -> Vec<usize> {
+        if self.baseline.is_none() {
+            self.baseline = Some(self.measure());
         }
+        if self.paused {
+            return vec![];
+        }
+        let accs = self.accelerations(&self.bodies);
+        let extreme: Vec<usize> = (0..accs.len())
+            .filter(|&i| accs[i].norm() > self.metadata.max_acceleration)
+            .collect();
+        let limited = self.limit(accs.clone());
+        set_accelerations(&mut self.bodies, limited);
         let mut removes: Vec<usize> = vec![];
-        for i in 0..self.bodies.len() {
-            let body = &mut self.bodies[i];
-            match body.accelerate(time_delta * self.metadata.time_scaling) {
-                Err(_) => removes.push(i),
-                _ => body.movement(time_delta * self.metadata.time_scaling),
+        let mut substeps = 1;
+        //Each crossing of the threshold is reported once, not every step a clamped body stays above it
+        let crossed: Vec<usize> = extreme
+            .iter()
+            .copied()
+            .filter(|i| !self.extreme.contains(i))
+            .collect();
+        self.extreme = extreme.iter().copied().collect();
+        if !extreme.is_empty() {
+            let policy = self.metadata.acceleration_policy;
+            for &index in &crossed {
+                event_sender
+                    .send(SimEvent::ExtremeAcceleration {
+                        index,
+                        acceleration: accs[index].norm(),
+                        policy,
+                    })
+                    .unwrap();
+            }
+            match policy {
+                AccelerationPolicy::Remove => {
+                    //The client only knows the massive bodies by their index
+                    for &r in extreme.iter().rev() {
+                        if self.bodies[r].particle {
+                            self.bodies.remove(r);
+                            self.reindex(removal(r, None), event_sender.clone());
+                        } else {
+                            self.handle_input(InputEvent::Remove(r), None, event_sender.clone())
+                                .expect("Removing a body cannot fail.");
+                        }
+                        removes.push(r);
+                    }
+                }
+                AccelerationPolicy::Merge => {
+                    removes = self.merge_with_nearest(extreme, event_sender.clone());
+                }
+                AccelerationPolicy::Pause => {
+                    self.paused = true;
+                    return removes;
+                }
+                AccelerationPolicy::ReduceStep => {
+                    let peak = self.bodies.iter().map(|b| b.acc.norm()).fold(0.0, f64::max);
+                    substeps = ((peak / self.metadata.max_acceleration).ceil() as usize)
+                        .clamp(1, MAX_REDUCTION);
+                }
+                //The accelerations are already limited by `limit`
+                AccelerationPolicy::Clamp => {}
+            }
+        }
+        self.manoeuvre(sim_time_delta, event_sender.clone());
+
+        let before: Vec<(Vector3<f64>, Vector3<f64>)> =
+            self.bodies.iter().map(|b| (b.pos, b.vel)).collect();
+        let integrator = self.metadata.integrator();
+        let mut bodies = std::mem::take(&mut self.bodies);
+        let dt = sim_time_delta / substeps as f64;
+        let mut report = StepReport::new(0, 0.0);
+        for substep in 0..substeps {
+            //Not every integrator leaves the acceleration of the new state in `acc`
+            if substep > 0 {
+                let accs = self.limited_accelerations(&bodies);
+                set_accelerations(&mut bodies, accs);
+            }
+            report.add(integrator.step(&mut bodies, dt, &|b| self.limited_accelerations(b)));
+        }
+        self.report.add(report);
+        self.bodies = bodies;
+        self.metadata.time += sim_time_delta;
+        self.detect_encounters(&before, sim_time_delta, event_sender.clone());
+        let count = self.bodies.len();
+        removes.append(&mut self.resolve_collisions(event_sender.clone()));
+        if self.metadata.tidal_disruption {
+            self.disrupt(event_sender);
+        }
+        //The indices of the pairs are outdated once bodies are removed or inserted
+        if !removes.is_empty() || count != self.bodies.len() {
+            self.encounters.clear();
+            self.extreme.clear();
+        }
+        removes
+    }
+
+    ///The accelerations of `accelerations`, limited to the `max_acceleration` of the metadata if the
+    ///`AccelerationPolicy` is `Clamp`
+    fn limited_accelerations(&self, bodies: &[Body]) -> Vec<Vector3<f64>> {
+        self.limit(self.accelerations(bodies))
+    }
+
+    ///Scales every acceleration of `accs` above the `max_acceleration` of the metadata down to it, if the
+    ///`AccelerationPolicy` is `Clamp`
+    fn limit(&self, mut accs: Vec<Vector3<f64>>) -> Vec<Vector3<f64>> {
+        if self.metadata.acceleration_policy == AccelerationPolicy::Clamp {
+            for acc in accs.iter_mut() {
+                let norm = acc.norm();
+                if norm > self.metadata.max_acceleration {
+                    *acc *= self.metadata.max_acceleration / norm;
+                }
+            }
+        }
+        accs
+    }
+
+    ///Merges each body at the indices `extreme` into the nearest massive body. Test particles have nothing to
+    ///merge and are removed. Returns the indices of the removed bodies in the order of removal.
+    fn merge_with_nearest(
+        &mut self,
+        mut extreme: Vec<usize>,
+        event_sender: crossbeam_channel::Sender<SimEvent>,
+    ) -> Vec<usize> {
+        let mut removes = vec![];
+        while let Some(i) = extreme.pop() {
+            let nearest = (0..massive_count(&self.bodies))
+                .filter(|&k| k != i)
+                .min_by(|&a, &b| {
+                    let distance = |k: usize| (self.bodies[k].pos - self.bodies[i].pos).norm();
+                    distance(a).total_cmp(&distance(b))
+                });
+            let removed = match nearest {
+                Some(k) if !self.bodies[i].particle => {
+                    let (survivor, removed) = (i.min(k), i.max(k));
+                    self.bodies[survivor] = self.bodies[i].merge(&self.bodies[k]);
+                    self.bodies.remove(removed);
+                    event_sender
+                        .send(SimEvent::Collision {
+                            survivor,
+                            removed,
+                            mass: self.bodies[survivor].mass,
+                            radius: self.bodies[survivor].radius,
+                        })
+                        .unwrap();
+                    self.reindex(removal(removed, Some(survivor)), event_sender.clone());
+                    removed
+                }
+                _ => {
+                    if !self.bodies.remove(i).particle {
+                        event_sender.send(SimEvent::Removed(i)).unwrap();
+                    }
+                    self.reindex(removal(i, None), event_sender.clone());
+                    i
+                }
+            };
+            removes.push(removed);
+            //Bodies behind the removed one move up. One merged away needs no handling of its own
+            extreme.retain(|&k| k != removed);
+            for k in extreme.iter_mut() {
+                if *k > removed {
+                    *k -= 1;
+                }
             }
         }
-        removes.reverse();
-        for r in &removes {
-            self.handle_input(InputEvent::Remove(*r), None, remove_sender.clone())
+        removes
+    }
+
+    ///Handles all pairs of overlapping bodies according to the `CollisionPolicy` of the metadata.
+    ///Returns the indices of the removed bodies in the order of removal.
+    fn resolve_collisions(
+        &mut self,
+        event_sender: crossbeam_channel::Sender<SimEvent>,
+    ) -> Vec<usize> {
+        let mut removes = vec![];
+        let mut i = 0;
+        'outer: while i < massive_count(&self.bodies) {
+            let mut k = i + 1;
+            while k < massive_count(&self.bodies) {
+                if !self.bodies[i].overlaps(&self.bodies[k]) {
+                    k += 1;
+                    continue;
+                }
+                let merged = self.bodies[i].merge(&self.bodies[k]);
+                let fragment_mass = merged.mass / self.metadata.fragments as f64;
+                match self.metadata.collision_policy {
+                    CollisionPolicy::Fragment
+                        if self.metadata.fragments >= 2
+                            && fragment_mass >= self.metadata.min_fragment_mass =>
+                    {
+                        let normal = self.bodies[k].pos - self.bodies[i].pos;
+                        let mut fragments = merged.fragment(
+                            self.metadata.fragments,
+                            &normal,
+                            self.metadata.interaction_constant,
+                        );
+                        let first_new = massive_count(&self.bodies);
+                        self.bodies[k] = fragments.pop().unwrap();
+                        self.bodies[i] = fragments.pop().unwrap();
+                        self.bodies.splice(first_new..first_new, fragments);
+                        event_sender
+                            .send(SimEvent::Fragmentation {
+                                first: i,
+                                second: k,
+                                first_new,
+                                fragments: self.metadata.fragments,
+                            })
+                            .unwrap();
+                        self.reindex(
+                            insertion(first_new, self.metadata.fragments - 2),
+                            event_sender.clone(),
+                        );
+                        k = i + 1;
+                    }
+                    CollisionPolicy::Bounce => {
+                        let impulse = self.bounce(i, k);
+                        if impulse > 0.0 {
+                            event_sender
+                                .send(SimEvent::Bounce {
+                                    first: i,
+                                    second: k,
+                                    impulse,
+                                })
+                                .unwrap();
+                        }
+                        k += 1;
+                    }
+                    CollisionPolicy::Remove => {
+                        for r in [k, i] {
+                            self.bodies.remove(r);
+                            removes.push(r);
+                            event_sender.send(SimEvent::Removed(r)).unwrap();
+                            self.reindex(removal(r, None), event_sender.clone());
+                        }
+                        continue 'outer;
+                    }
+                    //Fragments below the minimal mass merge instead, so fragmentation cannot cascade endlessly
+                    CollisionPolicy::Merge | CollisionPolicy::Fragment => {
+                        self.bodies[i] = merged;
+                        self.bodies.remove(k);
+                        removes.push(k);
+                        event_sender
+                            .send(SimEvent::Collision {
+                                survivor: i,
+                                removed: k,
+                                mass: self.bodies[i].mass,
+                                radius: self.bodies[i].radius,
+                            })
+                            .unwrap();
+                        self.reindex(removal(k, Some(i)), event_sender.clone());
+                        //The merged body has grown, so it may now reach bodies that were checked before
+                        k = i + 1;
+                    }
+                }
+            }
+            i += 1;
+        }
+        //Test particles hitting a massive body are absorbed without changing it. The client only knows the massive
+        //bodies by their index, so it is not notified
+        for p in (massive_count(&self.bodies)..self.bodies.len()).rev() {
+            if self.bodies[..massive_count(&self.bodies)]
+                .iter()
+                .any(|b| b.overlaps(&self.bodies[p]))
+            {
+                self.bodies.remove(p);
+                removes.push(p);
+                self.reindex(removal(p, None), event_sender.clone());
+            }
+        }
+        removes
+    }
+
+    ///Breaks every body inside the Roche limit of a more massive body into a ring of `fragments` pieces in its
+    ///orbital plane around that body. Fragments below the minimal mass of the metadata are not created.
+    fn disrupt(&mut self, event_sender: crossbeam_channel::Sender<SimEvent>) {
+        let count = self.metadata.fragments;
+        let mut i = 0;
+        while i < massive_count(&self.bodies) {
+            let body = &self.bodies[i];
+            if body.debris
+                || count < 2
+                || body.mass / (count as f64) < self.metadata.min_fragment_mass
+            {
+                i += 1;
+                continue;
+            }
+            let primary = (0..massive_count(&self.bodies)).find(|&k| {
+                let other = &self.bodies[k];
+                other.mass > body.mass && (other.pos - body.pos).norm() < body.roche_limit(other)
+            });
+            if let Some(k) = primary {
+                let normal =
+                    (body.pos - self.bodies[k].pos).cross(&(body.vel - self.bodies[k].vel));
+                let mut fragments =
+                    body.fragment(count, &normal, self.metadata.interaction_constant);
+                for fragment in fragments.iter_mut() {
+                    fragment.debris = true;
+                }
+                let first_new = massive_count(&self.bodies);
+                self.bodies[i] = fragments.pop().unwrap();
+                self.bodies.splice(first_new..first_new, fragments);
+                event_sender
+                    .send(SimEvent::Disruption {
+                        index: i,
+                        primary: k,
+                        first_new,
+                        fragments: count,
+                    })
+                    .unwrap();
+                self.reindex(insertion(first_new, count - 1), event_sender.clone());
+            }
+            i += 1;
         }
-        return removes;
     }
 
+    ///Lets the bodies at `i` and `k` bounce off each other with the `restitution` of the metadata and pushes
+    ///them apart until they touch. Returns the transferred momentum, which is 0 if they are already separating.
+    fn bounce(&mut self, i: usize, k: usize) -> f64 {
+        let (m1, m2) = (self.bodies[i].mass, self.bodies[k].mass);
+        let r = self.bodies[k].pos - self.bodies[i].pos;
+        let distance = r.norm();
+        let normal = if distance > 0.0 {
+            r / distance
+        } else {
+            Vector3::x()
+        };
+        let overlap = self.bodies[i].radius + self.bodies[k].radius - distance;
+        self.bodies[i].pos -= normal * overlap * m2 / (m1 + m2);
+        self.bodies[k].pos += normal * overlap * m1 / (m1 + m2);
+
+        let approach = (self.bodies[k].vel - self.bodies[i].vel).dot(&normal);
+        if approach >= 0.0 {
+            return 0.0;
+        }
+        let impulse = -(1.0 + self.metadata.restitution) * approach / (1.0 / m1 + 1.0 / m2);
+        self.bodies[i].vel -= normal * impulse / m1;
+        self.bodies[k].vel += normal * impulse / m2;
+        impulse
+    }
+
+    fn measure(&self) -> Diagnostics {
+        Diagnostics::measure(
+            &self.bodies,
+            self.metadata.interaction_constant,
+            self.metadata.softening,
+            self.metadata.softening_length,
+        )
+    }
+
+    ///The conserved quantities of the current state and their drift since the last change by the client
+    pub fn diagnostics(&self) -> Diagnostics {
+        let current = self.measure();
+        match &self.baseline {
+            Some(baseline) => current.compare(baseline),
+            None => current,
+        }
+    }
+
+    ///Simulated seconds since the start
+    pub fn time(&self) -> f64 {
+        self.metadata.time
+    }
+
+    ///Whether an extreme acceleration stopped the simulation, see `AccelerationPolicy::Pause`
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    ///Position and velocity of the center of mass of all bodies
+    fn barycentre(&self) -> (Vector3<f64>, Vector3<f64>) {
+        let mass: f64 = self.bodies.iter().map(|b| b.mass).sum();
+        if mass == 0.0 {
+            return (Vector3::zeros(), Vector3::zeros());
+        }
+        let pos = self
+            .bodies
+            .iter()
+            .map(|b| b.pos * b.mass)
+            .sum::<Vector3<f64>>()
+            / mass;
+        let vel = self
+            .bodies
+            .iter()
+            .map(|b| b.vel * b.mass)
+            .sum::<Vector3<f64>>()
+            / mass;
+        (pos, vel)
+    }
+
+    ///The elements of the orbit of the body at index `body` around the one at index `parent`, using the
+    ///interaction constant of the metadata
+    pub fn orbital_elements(&self, body: usize, parent: usize) -> OrbitalElements {
+        let (body, parent) = (&self.bodies[body], &self.bodies[parent]);
+        let mu = self.metadata.interaction_constant * (body.mass + parent.mass);
+        OrbitalElements::from_state(&(body.pos - parent.pos), &(body.vel - parent.vel), mu)
+    }
+
+    ///Shifts all bodies into the barycentric frame, so the center of mass rests in the origin
+    ///and the total momentum vanishes
+    pub fn recenter(&mut self) {
+        let (pos, vel) = self.barycentre();
+        for body in self.bodies.iter_mut() {
+            body.pos -= pos;
+            body.vel -= vel;
+        }
+        self.baseline = None;
+    }
+
+    ///Position and velocity of the origin of the output `Frame` selected in the metadata
+    fn frame_origin(&self) -> (Vector3<f64>, Vector3<f64>) {
+        match self.metadata.frame {
+            Frame::Inertial => (Vector3::zeros(), Vector3::zeros()),
+            Frame::Barycentre => self.barycentre(),
+            Frame::Body => match self.bodies.get(self.metadata.frame_body) {
+                Some(body) => (body.pos, body.vel),
+                None => (Vector3::zeros(), Vector3::zeros()),
+            },
+        }
+    }
+
+    ///Copies the current state for sending it to the client. Positions and velocities are given
+    ///in the output `Frame` of the metadata. The diagnostics and the hierarchy are only measured every
+    ///`analysis_interval` snapshots and the first one after a change by the client.
+    pub fn snapshot(&mut self) -> Snapshot {
+        let interval = self.metadata.analysis_interval;
+        if interval == 0 {
+            self.diagnostics = None;
+            self.hierarchy = None;
+        } else if self.frames % interval == 0 {
+            self.diagnostics = Some(self.diagnostics());
+            self.hierarchy = Some(self.hierarchy());
+        }
+        self.frames += 1;
+        let (origin_pos, origin_vel) = self.frame_origin();
+        let n = massive_count(&self.bodies);
+        let mut bodies = self.bodies[..n].to_vec();
+        for body in bodies.iter_mut() {
+            body.pos -= origin_pos;
+            body.vel -= origin_vel;
+        }
+        Snapshot {
+            bodies,
+            particles: self.bodies[n..]
+                .iter()
+                .map(|p| p.pos - origin_pos)
+                .collect(),
+            metadata: self.metadata.clone(),
+            report: std::mem::replace(&mut self.report, StepReport::new(0, 0.0)),
+            diagnostics: self.diagnostics,
+            hierarchy: self.hierarchy.clone(),
+        }
+    }
+
+    ///Adds `body` behind the other bodies of its kind, so the indices of all massive bodies stay the same
+    pub fn add_body(&mut self, body: Body) {
+        match body.particle {
+            true => self.bodies.push(body),
+            false => {
+                let at = massive_count(&self.bodies);
+                self.bodies.insert(at, body);
+                //Only particles move, which cannot be gone afterwards, so nothing needs to be reported
+                let (sender, _receiver) = crossbeam_channel::unbounded();
+                self.reindex(insertion(at, 1), sender);
+            }
+        }
+    }
+
+    ///Keeps the body indices of the metadata pointing at the same bodies. `moved` maps the old index of a body
+    ///to the new one, or to `None` if the body is gone. Indices of bodies that are gone are reset to 0, which
+    ///is reported if they are in use.
+    fn reindex(
+        &mut self,
+        moved: impl Fn(usize) -> Option<usize>,
+        event_sender: crossbeam_channel::Sender<SimEvent>,
+    ) {
+        match moved(self.metadata.frame_body) {
+            Some(k) => self.metadata.frame_body = k,
+            None => {
+                self.metadata.frame_body = 0;
+                if self.metadata.frame == Frame::Body {
+                    event_sender
+                        .send(SimEvent::Reset {
+                            parameter: "frame_body".to_string(),
+                        })
+                        .unwrap();
+                }
+            }
+        }
+        for force in self.metadata.forces.iter_mut() {
+            for parameter in force.reindex(&moved) {
+                event_sender
+                    .send(SimEvent::Reset {
+                        parameter: format!("{}.{}", force.name(), parameter),
+                    })
+                    .unwrap();
+            }
+        }
+    }
+
+    ///Applies the input `event_type` of the client. `new_body` is required by `InputEvent::Add` and
+    ///`InputEvent::Update`.
+    ///
+    /// # Error
+    ///
+    /// Fails if a manoeuvre targets a body that does not exist or is no spacecraft.
     pub fn handle_input(
         &mut self,
         event_type: InputEvent,
         new_body: Option<Body>,
-        remove_sender: crossbeam_channel::Sender<usize>,
-    ) {
+        event_sender: crossbeam_channel::Sender<SimEvent>,
+    ) -> Res<()> {
+        self.baseline = None;
+        self.encounters.clear();
+        self.paused = false;
+        self.extreme.clear();
+        self.frames = 0;
+        //The client sees the bodies in the output frame, so new bodies are given in it as well
+        let (origin_pos, origin_vel) = self.frame_origin();
+        let new_body = new_body.map(|b| {
+            let pos = b.pos + origin_pos;
+            let vel = b.vel + origin_vel;
+            b.set_position(pos).set_velocity(vel)
+        });
         match event_type {
-            InputEvent::Add => self
-                .bodies
-                .push(new_body.expect("No Body was provided with 'InputEvent::Add'.")),
+            InputEvent::Add => {
+                self.add_body(new_body.expect("No Body was provided with 'InputEvent::Add'."))
+            }
             InputEvent::Remove(target) => {
                 self.bodies.remove(target);
-                remove_sender.send(target).unwrap();
+                event_sender.send(SimEvent::Removed(target)).unwrap();
+                self.reindex(removal(target, None), event_sender);
             }
             InputEvent::Update(target) => {
-                let update = new_body.expect("No Body was provided with 'InputEvent::Update'.");
+                let mut update = new_body.expect("No Body was provided with 'InputEvent::Update'.");
+                //Changing the kind would break the order of massive bodies and particles, so only the state is
+                //taken over
+                if update.particle != self.bodies[target].particle {
+                    update = self.bodies[target]
+                        .clone()
+                        .set_position(update.pos)
+                        .set_velocity(update.vel);
+                }
+                //The client does not know the schedule, so a spacecraft keeps it
+                if update.spacecraft.is_none() {
+                    update.spacecraft = self.bodies[target].spacecraft.take();
+                }
                 self.bodies[target] = update;
             }
-            InputEvent::Meta => {}
+            InputEvent::Manoeuvre(target, manoeuvre) => {
+                let count = self.bodies.len();
+                self.bodies
+                    .get_mut(target)
+                    .ok_or(format!(
+                        "Invalid manoeuvre. There are only {count} bodies, but found {target}."
+                    ))?
+                    .add_manoeuvre(manoeuvre)?;
+            }
+            InputEvent::Recenter => self.recenter(),
+            InputEvent::Meta | InputEvent::Preset(_) => {}
         }
+        Ok(())
     }
 
     pub fn handle_meta_input(&mut self, meta_state: SimMetaData) {
+        self.paused = false;
+        self.extreme.clear();
+        self.frames = 0;
+        if meta_state.interaction_constant != self.metadata.interaction_constant
+            || meta_state.softening != self.metadata.softening
+            || meta_state.softening_length != self.metadata.softening_length
+        {
+            self.baseline = None;
+        }
         self.metadata = meta_state
     }
 }
@@ -1,39 +1,92 @@
 mod webserver;
-use interstellare_simulation::{Body, InputEvent, Res, SimMetaData, SimState};
-use std::env;
+use interstellare_simulation::{
+    preset, Body, Generator, InputEvent, Manoeuvre, Pacer, Pacing, Res, SimEvent, SimState,
+    Snapshot,
+};
+use std::{
+    env,
+    sync::{Arc, Mutex},
+};
 
 fn main() -> Res<()> {
     let args: Vec<String> = env::args().collect();
-    let presentation_mode = args.len() > 1 && args[1] == "-p";
+    let presentation_mode = args.iter().any(|a| a == "-p");
+    let barycentric = args.iter().any(|a| a == "--barycentric");
+    let epoch = option_value(&args, "--epoch")?;
+    let scenario = option_value(&args, "--scenario")?;
+    let generator = option_value(&args, "--generator")?
+        .map(Generator::from_string)
+        .transpose()?;
+    let seed = option_value(&args, "--seed")?.map_or(Ok(0), str::parse::<u64>)?;
     println!("{:?}", args);
     //crossbeam_channel are used to communicate between the simulation thread and the server thread.
     let (input_sender, input_receiver) = crossbeam_channel::unbounded::<String>();
-    let (simulation_sender, simulation_receiver) =
-        crossbeam_channel::unbounded::<(Vec<Body>, SimMetaData)>();
+    let (simulation_sender, simulation_receiver) = crossbeam_channel::unbounded::<Snapshot>();
 
-    let (remove_sender, remove_receiver) = crossbeam_channel::unbounded::<usize>();
+    let (event_sender, event_receiver) = crossbeam_channel::unbounded::<SimEvent>();
+    //The last snapshot sent, for queries of the server
+    let latest_snapshot = Arc::new(Mutex::new(None));
+    let server_snapshot = latest_snapshot.clone();
 
     std::thread::spawn(move || {
         webserver::spawn(
             input_sender,
             simulation_receiver,
-            remove_receiver,
+            event_receiver,
+            server_snapshot,
             presentation_mode,
         )
         .unwrap();
     });
 
-    simulation(input_receiver, simulation_sender, remove_sender)
+    let mut sim = match (scenario, epoch) {
+        (Some(_), Some(_)) => return Err("Use either '--scenario' or '--epoch', not both.".into()),
+        (Some(name), None) => preset(name)?.build(0.01, 300000.0),
+        (None, Some(date)) => SimState::at_epoch(date, 0.01, 300000.0)?,
+        (None, None) => SimState::new(0.01, 300000.0),
+    };
+    if let Some(generator) = generator {
+        sim = generator.build(sim, seed);
+    }
+    if barycentric {
+        sim.recenter();
+    }
+    simulation(
+        sim,
+        input_receiver,
+        simulation_sender,
+        event_sender,
+        latest_snapshot,
+    )
+}
+
+///The value following the command line option `name`, if the option is given.
+///
+/// # Error
+///
+/// Fails if the option is the last argument.
+fn option_value<'a>(args: &'a [String], name: &str) -> Res<Option<&'a str>> {
+    match args.iter().position(|a| a == name) {
+        Some(i) => match args.get(i + 1) {
+            Some(value) => Ok(Some(value)),
+            None => Err(format!("Missing value after '{name}'.").into()),
+        },
+        None => Ok(None),
+    }
 }
 
 ///Takes the `input` from the Client and updates `sim` accordingly.
 ///
 /// # Panic
 /// Panics when the input is in an invalid form.
+///
+/// # Error
+///
+/// Fails if the event type is unknown or the simulation rejects the input.
 fn handle_input(
     sim: &mut SimState,
     input: String,
-    remove_sender: crossbeam_channel::Sender<usize>,
+    event_sender: crossbeam_channel::Sender<SimEvent>,
 ) -> Res<()> {
     let trimed = input
         .trim()
@@ -57,7 +110,7 @@ fn handle_input(
             trimed = trimed.strip_prefix("\"body\":").unwrap().to_string();
             InputEvent::Add
         }
-        x if x == "Remove" || x == "Update" => {
+        x if x == "Remove" || x == "Update" || x == "Manoeuvre" => {
             trimed = trimed
                 .strip_prefix("\"body\":{\"")
                 .unwrap()
@@ -74,13 +127,24 @@ fn handle_input(
             match x {
                 "Remove" => InputEvent::Remove(target),
                 "Update" => InputEvent::Update(target),
+                "Manoeuvre" => InputEvent::Manoeuvre(target, Manoeuvre::from_string(&trimed)?),
                 _ => return Err("Unreachable. Something went wrong.".into()),
             }
         }
         "Meta" => InputEvent::Meta,
+        "Recenter" => InputEvent::Recenter,
+        "Preset" => InputEvent::Preset(
+            trimed
+                .split_once(":")
+                .unwrap()
+                .1
+                .trim()
+                .trim_matches('"')
+                .to_string(),
+        ),
         x => {
             return Err(format!(
-                "Invalid 'event_type'. Expected 'Add', 'Remove', 'Meta' or 'Update', but found {x}."
+                "Invalid 'event_type'. Expected 'Add', 'Remove', 'Meta', 'Update', 'Recenter', 'Preset' or 'Manoeuvre', but found {x}."
             )
             .into());
         }
@@ -88,13 +152,19 @@ fn handle_input(
     let new_body = match event_type {
         InputEvent::Remove(_) => None,
         InputEvent::Meta => None,
+        InputEvent::Recenter => None,
+        InputEvent::Preset(_) => None,
+        InputEvent::Manoeuvre(..) => None,
         _ => Some(Body::from_string(&trimed)?),
     };
     match event_type {
         InputEvent::Meta => {
-            sim.handle_meta_input(SimMetaData::from_string(trimed.split_once(":").unwrap().1)?)
+            let mut meta = sim.metadata.clone();
+            meta.update(trimed.split_once(":").unwrap().1)?;
+            sim.handle_meta_input(meta)
         }
-        _ => sim.handle_input(event_type, new_body, remove_sender),
+        InputEvent::Preset(name) => sim.load_preset(&name, event_sender)?,
+        _ => sim.handle_input(event_type, new_body, event_sender)?,
     }
     Ok(())
 }
@@ -102,17 +172,24 @@ fn handle_input(
 /// The main loop of the simulation. It reads the input from the client and sends each simulation step
 ///
 fn simulation(
+    mut sim: SimState,
     input_receiver: crossbeam_channel::Receiver<String>,
-    simulation_sender: crossbeam_channel::Sender<(Vec<Body>, SimMetaData)>,
-    remove_sender: crossbeam_channel::Sender<usize>,
+    simulation_sender: crossbeam_channel::Sender<Snapshot>,
+    event_sender: crossbeam_channel::Sender<SimEvent>,
+    latest_snapshot: Arc<Mutex<Option<Snapshot>>>,
 ) -> Res<()> {
-    let mut sim = SimState::new(0.01, 300000.0);
+    let mut pacer = Pacer::new(sim.target_time_per_step_s);
     let mut last_send = std::time::Instant::now();
     let mut last_sim = std::time::Instant::now();
     loop {
         //unblocking read of the input_receiver. So if no input is there the loop can continue
         match input_receiver.try_recv() {
-            Ok(i) => handle_input(&mut sim, i, remove_sender.clone())?,
+            //Invalid input is rejected, but does not end the simulation
+            Ok(i) => {
+                if let Err(e) = handle_input(&mut sim, i, event_sender.clone()) {
+                    println!("Rejected input: {e}");
+                }
+            }
             Err(e) => {
                 if e == crossbeam_channel::TryRecvError::Disconnected {
                     return Err("Input disconected. Ending simulation".into());
@@ -121,14 +198,29 @@ fn simulation(
         }
         let time_delta = last_sim.elapsed().as_secs_f64();
 
-        sim.time_step(time_delta, remove_sender.clone());
+        // In the wall pacing the real time drives the step size directly. All other pacings run steps of
+        // constant size and only decide how many of them are due.
+        match sim.metadata.pacing() {
+            Pacing::Wall => {
+                sim.time_step(time_delta, event_sender.clone());
+            }
+            _ => {
+                for _ in 0..pacer.steps_due(&sim.metadata, time_delta) {
+                    sim.fixed_step(event_sender.clone());
+                }
+            }
+        }
 
         last_sim = std::time::Instant::now();
 
         // If to many events are send to the client it may cause performance issues.
         // To not overwhelm the client a minimum amount of time has to pass before sending new data
         if last_send.elapsed().as_secs_f64() > sim.target_time_per_step_s {
-            simulation_sender.send((sim.bodies.clone(), sim.metadata.clone()))?;
+            let snapshot = sim.snapshot();
+            *latest_snapshot
+                .lock()
+                .map_err(|_| "The server stopped. Ending simulation")? = Some(snapshot.clone());
+            simulation_sender.send(snapshot)?;
             last_send = std::time::Instant::now();
         }
     }
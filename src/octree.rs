@@ -0,0 +1,151 @@
+use crate::Body;
+use na::Vector3;
+
+///Depth after which nodes are no longer split. Guards against endless splitting for bodies at the same position
+const MAX_DEPTH: usize = 32;
+
+struct Node {
+    center: Vector3<f64>,
+    half_size: f64,
+    mass: f64,
+    ///Mass weighted sum of the positions while building, the center of mass afterwards
+    com: Vector3<f64>,
+    ///Index of the first of the eight consecutive children
+    children: Option<usize>,
+    bodies: Vec<usize>,
+}
+
+impl Node {
+    fn new(center: Vector3<f64>, half_size: f64) -> Node {
+        Node {
+            center,
+            half_size,
+            mass: 0.0,
+            com: Vector3::zeros(),
+            children: None,
+            bodies: vec![],
+        }
+    }
+
+    ///The index of the child octant `pos` falls into
+    fn octant(&self, pos: &Vector3<f64>) -> usize {
+        (pos.x >= self.center.x) as usize
+            | ((pos.y >= self.center.y) as usize) << 1
+            | ((pos.z >= self.center.z) as usize) << 2
+    }
+
+    fn contains(&self, pos: &Vector3<f64>) -> bool {
+        (pos - self.center).abs().max() <= self.half_size
+    }
+}
+
+///Barnes-Hut octree. Approximates the gravitation of far away groups of bodies by their total mass
+///in their center of mass, reducing the cost of the force calculation to O(N log N).
+pub struct Octree {
+    nodes: Vec<Node>,
+}
+
+impl Octree {
+    pub fn new(bodies: &[Body]) -> Octree {
+        let mut min = Vector3::repeat(f64::INFINITY);
+        let mut max = Vector3::repeat(f64::NEG_INFINITY);
+        for body in bodies {
+            min = min.inf(&body.pos);
+            max = max.sup(&body.pos);
+        }
+        let (center, half_size) = if bodies.is_empty() {
+            (Vector3::zeros(), 1.0)
+        } else {
+            let extent = (max - min).max();
+            ((min + max) * 0.5, (0.5 * extent).max(1.0) * 1.0001)
+        };
+        let mut tree = Octree {
+            nodes: vec![Node::new(center, half_size)],
+        };
+        for i in 0..bodies.len() {
+            tree.insert(0, i, bodies, 0);
+        }
+        for node in tree.nodes.iter_mut() {
+            node.com = if node.mass > 0.0 {
+                node.com / node.mass
+            } else {
+                node.center
+            };
+        }
+        tree
+    }
+
+    fn insert(&mut self, node: usize, i: usize, bodies: &[Body], depth: usize) {
+        let body = &bodies[i];
+        self.nodes[node].mass += body.mass;
+        self.nodes[node].com += body.pos * body.mass;
+        if let Some(first) = self.nodes[node].children {
+            let child = first + self.nodes[node].octant(&body.pos);
+            self.insert(child, i, bodies, depth + 1);
+            return;
+        }
+        if self.nodes[node].bodies.is_empty() || depth >= MAX_DEPTH {
+            self.nodes[node].bodies.push(i);
+            return;
+        }
+        //Split the leaf and move its bodies down
+        let first = self.nodes.len();
+        let center = self.nodes[node].center;
+        let quarter = 0.5 * self.nodes[node].half_size;
+        for octant in 0..8 {
+            let offset = Vector3::new(
+                if octant & 1 == 1 { quarter } else { -quarter },
+                if octant & 2 == 2 { quarter } else { -quarter },
+                if octant & 4 == 4 { quarter } else { -quarter },
+            );
+            self.nodes.push(Node::new(center + offset, quarter));
+        }
+        self.nodes[node].children = Some(first);
+        for k in std::mem::take(&mut self.nodes[node].bodies) {
+            let child = first + self.nodes[node].octant(&bodies[k].pos);
+            self.insert(child, k, bodies, depth + 1);
+        }
+        let child = first + self.nodes[node].octant(&body.pos);
+        self.insert(child, i, bodies, depth + 1);
+    }
+
+    ///Approximates the acceleration on the body at index `i` of `bodies`. `bodies` starts with the bodies the
+    ///tree was build from, further bodies like test particles may follow.
+    ///
+    ///A node is treated as a single mass if its size seen from the body is smaller than `opening_angle`.
+    ///`kernel` returns the acceleration per unit mass of the source for the separation from the body to the source.
+    pub fn acceleration(
+        &self,
+        bodies: &[Body],
+        i: usize,
+        opening_angle: f64,
+        kernel: &dyn Fn(Vector3<f64>) -> Vector3<f64>,
+    ) -> Vector3<f64> {
+        let pos = bodies[i].pos;
+        let mut acc = Vector3::zeros();
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if node.mass == 0.0 {
+                continue;
+            }
+            match node.children {
+                None => {
+                    for &k in node.bodies.iter().filter(|k| **k != i) {
+                        acc += kernel(bodies[k].pos - pos) * bodies[k].mass;
+                    }
+                }
+                Some(first) => {
+                    let r = node.com - pos;
+                    let distance = r.norm();
+                    if !node.contains(&pos) && 2.0 * node.half_size < opening_angle * distance {
+                        acc += kernel(r) * node.mass;
+                    } else {
+                        stack.extend(first..first + 8);
+                    }
+                }
+            }
+        }
+        acc
+    }
+}
@@ -0,0 +1,132 @@
+use std::{f64::consts::PI, fmt::Display};
+
+use crate::{Body, Res, GRAVITATIONAL_CONSTANT};
+use na::{Rotation3, Vector3};
+
+///Below this, eccentricities count as circular and inclinations as equatorial
+const EPSILON: f64 = 1e-11;
+
+///Classical Keplerian elements of an orbit around a parent body. Angles are in radians, the semi-major axis
+///in m. Hyperbolic orbits have an eccentricity above 1 and a negative semi-major axis.
+///
+///For circular orbits the argument of periapsis is 0 and the true anomaly is measured from the ascending node.
+///For equatorial orbits the longitude of the ascending node is 0 and the node lies on the x-axis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OrbitalElements {
+    pub semi_major_axis: f64,
+    pub eccentricity: f64,
+    pub inclination: f64,
+    pub longitude_of_ascending_node: f64,
+    pub argument_of_periapsis: f64,
+    pub true_anomaly: f64,
+}
+
+impl Display for OrbitalElements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{\"a\": {}, \"e\": {}, \"i\": {}, \"omega_node\": {}, \"omega_periapsis\": {}, \"nu\": {}}}",
+            self.semi_major_axis,
+            self.eccentricity,
+            self.inclination,
+            self.longitude_of_ascending_node,
+            self.argument_of_periapsis,
+            self.true_anomaly
+        )
+    }
+}
+
+///Maps an angle to [0, 2π)
+fn normalize_angle(angle: f64) -> f64 {
+    angle.rem_euclid(2.0 * PI)
+}
+
+impl OrbitalElements {
+    ///Calculates the elements from the position `r` and velocity `v` relative to the parent.
+    ///`mu` is the interaction constant times the mass of both bodies.
+    pub fn from_state(r: &Vector3<f64>, v: &Vector3<f64>, mu: f64) -> OrbitalElements {
+        let h = r.cross(v);
+        let distance = r.norm();
+        let eccentricity_vector = ((v.norm_squared() - mu / distance) * r - r.dot(v) * v) / mu;
+        let eccentricity = eccentricity_vector.norm();
+        let semi_major_axis = 1.0 / (2.0 / distance - v.norm_squared() / mu);
+        let normal = h.normalize();
+        let inclination = normal.z.clamp(-1.0, 1.0).acos();
+
+        let node = Vector3::z().cross(&h);
+        let node = if node.norm() > EPSILON * h.norm() {
+            node.normalize()
+        } else {
+            Vector3::x()
+        };
+        let periapsis = if eccentricity > EPSILON {
+            eccentricity_vector / eccentricity
+        } else {
+            node
+        };
+        //Angle from `from` to `to` in the orbital plane, counted in the direction of the motion
+        let angle = |from: &Vector3<f64>, to: &Vector3<f64>| {
+            normalize_angle(normal.cross(from).dot(to).atan2(from.dot(to)))
+        };
+        OrbitalElements {
+            semi_major_axis,
+            eccentricity,
+            inclination,
+            longitude_of_ascending_node: normalize_angle(node.y.atan2(node.x)),
+            argument_of_periapsis: angle(&node, &periapsis),
+            true_anomaly: angle(&periapsis, r),
+        }
+    }
+
+    ///The position and velocity relative to the parent. `mu` is the interaction constant times the mass of
+    ///both bodies.
+    ///
+    /// # Error
+    ///
+    /// Fails for parabolic orbits and true anomalies a hyperbolic orbit never reaches.
+    pub fn to_state(&self, mu: f64) -> Res<(Vector3<f64>, Vector3<f64>)> {
+        let semi_latus_rectum = self.semi_major_axis * (1.0 - self.eccentricity.powi(2));
+        let denominator = 1.0 + self.eccentricity * self.true_anomaly.cos();
+        if semi_latus_rectum <= 0.0 || denominator <= 0.0 {
+            return Err(format!("The orbit {} cannot be reached.", self).into());
+        }
+        let (sin, cos) = self.true_anomaly.sin_cos();
+        let r = Vector3::new(cos, sin, 0.0) * semi_latus_rectum / denominator;
+        let v = Vector3::new(-sin, self.eccentricity + cos, 0.0) * (mu / semi_latus_rectum).sqrt();
+        let rotation =
+            Rotation3::from_axis_angle(&Vector3::z_axis(), self.longitude_of_ascending_node)
+                * Rotation3::from_axis_angle(&Vector3::x_axis(), self.inclination)
+                * Rotation3::from_axis_angle(&Vector3::z_axis(), self.argument_of_periapsis);
+        Ok((rotation * r, rotation * v))
+    }
+
+    ///Time for one revolution in s. Infinite for unbound orbits
+    pub fn period(&self, mu: f64) -> f64 {
+        if self.semi_major_axis <= 0.0 {
+            return f64::INFINITY;
+        }
+        2.0 * PI * (self.semi_major_axis.powi(3) / mu).sqrt()
+    }
+}
+
+impl Body {
+    ///Creates a body on the orbit given by `elements` around `parent`
+    pub fn from_elements(
+        mass: f64,
+        density: f64,
+        parent: &Body,
+        elements: &OrbitalElements,
+    ) -> Res<Body> {
+        let mu = GRAVITATIONAL_CONSTANT * (parent.mass + mass);
+        let (r, v) = elements.to_state(mu)?;
+        Ok(Body::new(mass, density)
+            .set_position(parent.pos + r)
+            .set_velocity(parent.vel + v))
+    }
+
+    ///The elements of the current orbit around `parent`
+    pub fn elements(&self, parent: &Body) -> OrbitalElements {
+        let mu = GRAVITATIONAL_CONSTANT * (parent.mass + self.mass);
+        OrbitalElements::from_state(&(self.pos - parent.pos), &(self.vel - parent.vel), mu)
+    }
+}
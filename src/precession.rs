@@ -0,0 +1,90 @@
+use std::f64::consts::PI;
+
+use crate::{Body, SimState, C};
+
+const ARCSEC_PER_RAD: f64 = 180.0 * 3600.0 / PI;
+const SECONDS_PER_CENTURY: f64 = 100.0 * 365.25 * 86400.0;
+
+///Perihelion precession of an orbit in arcseconds per century
+pub struct Precession {
+    ///Difference between the precession rate of a run with and one without the post-Newtonian correction
+    pub measured: f64,
+    ///Prediction of general relativity for the initial orbit
+    pub predicted: f64,
+}
+
+///Rotation rate of the Laplace-Runge-Lenz vector of the body at index 1 around the one at index 0 in rad/s.
+///The vector points to the perihelion, so its rotation is the precession. The rate is the slope of a linear fit
+///over `steps` fixed steps, which averages out the oscillation within each orbit.
+fn apsidal_rate(mut sim: SimState, steps: usize) -> f64 {
+    let (event_sender, _event_receiver) = crossbeam_channel::unbounded();
+    let g = sim.metadata.interaction_constant;
+    let mut samples: Vec<(f64, f64)> = Vec::with_capacity(steps);
+    let mut last_angle: Option<f64> = None;
+    let mut turns = 0.0;
+    for _ in 0..steps {
+        sim.fixed_step(event_sender.clone());
+        let (parent, body) = (&sim.bodies[0], &sim.bodies[1]);
+        let mu = g * (parent.mass + body.mass);
+        let r = body.pos - parent.pos;
+        let v = body.vel - parent.vel;
+        let lrl = v.cross(&r.cross(&v)) - mu * r / r.norm();
+        let angle = lrl.y.atan2(lrl.x);
+        //Unwrap the angle, so it changes continuously
+        if let Some(last) = last_angle {
+            if angle - last > PI {
+                turns -= 2.0 * PI;
+            } else if last - angle > PI {
+                turns += 2.0 * PI;
+            }
+        }
+        last_angle = Some(angle);
+        samples.push((sim.time(), angle + turns));
+    }
+    let n = samples.len() as f64;
+    let mean_t = samples.iter().map(|s| s.0).sum::<f64>() / n;
+    let mean_a = samples.iter().map(|s| s.1).sum::<f64>() / n;
+    let covariance: f64 = samples
+        .iter()
+        .map(|s| (s.0 - mean_t) * (s.1 - mean_a))
+        .sum();
+    let variance: f64 = samples.iter().map(|s| (s.0 - mean_t).powi(2)).sum();
+    covariance / variance
+}
+
+///Validation scenario for the post-Newtonian correction. Simulates `Body::sun()` and `Body::mercury()` alone for
+///`orbits` orbits with steps of `time_delta` seconds and compares the measured perihelion precession to the
+///prediction of general relativity.
+///
+///The Newtonian run is subtracted, so the precession caused by the integrator itself does not count.
+///`Body::mercury()` starts faster than the real planet, so its orbit is wider and the predicted precession is
+///lower than the famous 43 arcseconds per century.
+pub fn mercury_precession(orbits: f64, time_delta: f64) -> Precession {
+    let sim_with = |post_newtonian: bool| {
+        let mut sim = SimState::new(0.01, 1.0);
+        sim.bodies = vec![Body::sun(), Body::mercury()];
+        sim.metadata
+            .update(&format!(
+                "{{\"integrator\":\"yoshida\",\"fixed_dt\":{},\"post_newtonian\":{}}}",
+                time_delta, post_newtonian
+            ))
+            .unwrap();
+        sim
+    };
+    let sim = sim_with(false);
+    let (parent, body) = (&sim.bodies[0], &sim.bodies[1]);
+    let mu = sim.metadata.interaction_constant * (parent.mass + body.mass);
+    let r = body.pos - parent.pos;
+    let v = body.vel - parent.vel;
+    let semi_major_axis = 1.0 / (2.0 / r.norm() - v.norm_squared() / mu);
+    let semi_latus_rectum = r.cross(&v).norm_squared() / mu;
+    let period = 2.0 * PI * (semi_major_axis.powi(3) / mu).sqrt();
+    let per_orbit = 6.0 * PI * mu / (C * C * semi_latus_rectum);
+
+    let steps = (orbits * period / time_delta).ceil() as usize;
+    let measured = apsidal_rate(sim_with(true), steps) - apsidal_rate(sim, steps);
+    Precession {
+        measured: measured * ARCSEC_PER_RAD * SECONDS_PER_CENTURY,
+        predicted: per_orbit / period * ARCSEC_PER_RAD * SECONDS_PER_CENTURY,
+    }
+}
@@ -0,0 +1,259 @@
+use std::{f64::consts::PI, fmt::Display};
+
+use crate::{
+    ephemeris::{planetary_moons, J2000},
+    generators::Rng,
+    massive_count, Body, Generator, OrbitalElements, Res, SimEvent, SimState, AU,
+    GRAVITATIONAL_CONSTANT, SOLAR_MASS,
+};
+use na::Vector3;
+
+const EARTH_MASS: f64 = 5.9722e24;
+const EARTH_DENSITY: f64 = 5514.0;
+
+///A named scenario that can be selected at start up or loaded by the client
+pub struct Preset {
+    pub name: &'static str,
+    pub description: &'static str,
+    ///Replaces the bodies of the base simulation and adjusts its metadata where needed
+    build: fn(SimState) -> SimState,
+}
+
+impl Display for Preset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{\"name\": \"{}\", \"description\": \"{}\"}}",
+            self.name, self.description
+        )
+    }
+}
+
+impl Preset {
+    ///Builds the scenario. Everything besides the bodies is taken from `SimState::new`.
+    pub fn build(&self, target_time_per_step_s: f64, time_scaling: f64) -> SimState {
+        (self.build)(SimState::new(target_time_per_step_s, time_scaling))
+    }
+}
+
+///All presets. The first one is the default simulation.
+pub const PRESETS: &[Preset] = &[
+    Preset {
+        name: "solar_system",
+        description: "The sun, luna and the 8 planets",
+        build: |sim| sim,
+    },
+    Preset {
+        name: "figure_eight",
+        description: "Three equal stars chasing each other on a figure eight",
+        build: figure_eight,
+    },
+    Preset {
+        name: "pluto_charon",
+        description: "The binary dwarf planet Pluto and its moon Charon",
+        build: pluto_charon,
+    },
+    Preset {
+        name: "galilean_moons",
+        description: "Jupiter with Io, Europa, Ganymede and Callisto in their Laplace resonance",
+        build: galilean_moons,
+    },
+    Preset {
+        name: "trappist_1",
+        description: "The red dwarf TRAPPIST-1 with its seven earth-sized planets",
+        build: trappist_1,
+    },
+    Preset {
+        name: "alpha_centauri",
+        description: "The eccentric binary star Alpha Centauri A and B",
+        build: alpha_centauri,
+    },
+    Preset {
+        name: "sun_jupiter_trojans",
+        description: "The sun and Jupiter with swarms of Trojan asteroids around L4 and L5",
+        build: sun_jupiter_trojans,
+    },
+    Preset {
+        name: "asteroid_belt",
+        description: "The solar system with a belt of test particles between Mars and Jupiter",
+        build: |sim| Generator::AsteroidBelt.build(sim, 0),
+    },
+    Preset {
+        name: "plummer",
+        description: "A star cluster in virial equilibrium",
+        build: |sim| Generator::Plummer.build(sim, 0),
+    },
+    Preset {
+        name: "disc_galaxy",
+        description: "A rotating exponential disc of stars around a heavy center",
+        build: |sim| Generator::DiscGalaxy.build(sim, 0),
+    },
+    Preset {
+        name: "protoplanetary_disc",
+        description: "A young star surrounded by planetesimals",
+        build: |sim| Generator::ProtoplanetaryDisc.build(sim, 0),
+    },
+];
+
+///Looks up the preset called `name`
+///
+/// # Error
+///
+/// Fails if no preset has this name.
+pub fn preset(name: &str) -> Res<&'static Preset> {
+    let name = name.trim().trim_matches('"');
+    PRESETS.iter().find(|p| p.name == name).ok_or_else(|| {
+        let names: Vec<&str> = PRESETS.iter().map(|p| p.name).collect();
+        format!(
+            "Invalid preset. Expected one of '{}', but found {name}.",
+            names.join("', '")
+        )
+        .into()
+    })
+}
+
+///The bodies orbit `parent` circularly in its x-y-plane with the given semi-major axes. The phases are spread
+///by the golden angle.
+fn circular_orbits(parent: &Body, planets: &[(f64, f64, f64)]) -> Vec<Body> {
+    planets
+        .iter()
+        .enumerate()
+        .map(|(n, (mass, density, semi_major_axis))| {
+            let elements = OrbitalElements {
+                semi_major_axis: *semi_major_axis,
+                eccentricity: 0.0,
+                inclination: 0.0,
+                longitude_of_ascending_node: 0.0,
+                argument_of_periapsis: 0.0,
+                true_anomaly: n as f64 * PI * (3.0 - 5f64.sqrt()),
+            };
+            Body::from_elements(*mass, *density, parent, &elements)
+                .expect("Circular orbits are always reachable.")
+        })
+        .collect()
+}
+
+///Periodic solution of Chenciner and Montgomery, scaled to suns at a distance of about 1 AU
+fn figure_eight(mut sim: SimState) -> SimState {
+    let length = AU;
+    let speed = (GRAVITATIONAL_CONSTANT * SOLAR_MASS / length).sqrt();
+    let pos = Vector3::new(0.97000436, -0.24308753, 0.0) * length;
+    let vel = Vector3::new(-0.93240737, -0.86473146, 0.0) * speed;
+    sim.bodies = vec![
+        Body::sun().set_position(pos).set_velocity(-vel / 2.0),
+        Body::sun().set_position(-pos).set_velocity(-vel / 2.0),
+        Body::sun().set_velocity(vel),
+    ];
+    sim
+}
+
+fn pluto_charon(mut sim: SimState) -> SimState {
+    let pluto = Body::new(1.303e22, 1854.0);
+    let charon = circular_orbits(&pluto, &[(1.586e21, 1702.0, 1.9591e7)]);
+    sim.bodies = [vec![pluto], charon].concat();
+    sim.recenter();
+    sim
+}
+
+fn galilean_moons(mut sim: SimState) -> SimState {
+    sim.bodies =
+        planetary_moons("jupiter", J2000).expect("Jupiter is part of the ephemeris table.");
+    sim.set_epoch(Some(J2000));
+    sim
+}
+
+///Masses, radii and semi-major axes from Agol et al. (2021)
+fn trappist_1(mut sim: SimState) -> SimState {
+    let star = Body::new(0.0898 * SOLAR_MASS, 74800.0);
+    let planets: Vec<(f64, f64, f64)> = [
+        (1.374, 1.116, 0.01154),
+        (1.308, 1.097, 0.01580),
+        (0.388, 0.788, 0.02227),
+        (0.692, 0.920, 0.02925),
+        (1.039, 1.045, 0.03849),
+        (1.321, 1.129, 0.04683),
+        (0.326, 0.755, 0.06189),
+    ]
+    .iter()
+    .map(|&(mass, radius, a): &(f64, f64, f64)| {
+        (
+            mass * EARTH_MASS,
+            EARTH_DENSITY * mass / radius.powi(3),
+            a * AU,
+        )
+    })
+    .collect();
+    let planets = circular_orbits(&star, &planets);
+    sim.bodies = [vec![star], planets].concat();
+    sim.recenter();
+    sim
+}
+
+///Orbit of Pourbaix and Boyd (2016), turned into the x-y-plane. B starts in its apoapsis.
+fn alpha_centauri(mut sim: SimState) -> SimState {
+    let a = Body::new(1.0788 * SOLAR_MASS, 843.0);
+    let elements = OrbitalElements {
+        semi_major_axis: 23.3 * AU,
+        eccentricity: 0.5208,
+        inclination: 0.0,
+        longitude_of_ascending_node: 0.0,
+        argument_of_periapsis: 232.3f64.to_radians(),
+        true_anomaly: PI,
+    };
+    let b = Body::from_elements(0.9092 * SOLAR_MASS, 2022.0, &a, &elements)
+        .expect("Elliptic orbits are always reachable.");
+    sim.bodies = vec![a, b];
+    sim.recenter();
+    sim
+}
+
+///Jupiter on a circular orbit with test particles librating around its leading and trailing Lagrange points
+fn sun_jupiter_trojans(mut sim: SimState) -> SimState {
+    let sun = Body::sun();
+    let jupiter = circular_orbits(&sun, &[(1.89819e27, 1326.0, 5.2029 * AU)]);
+    sim.bodies = [vec![sun], jupiter].concat();
+    sim.recenter();
+    let (sun, jupiter) = (&sim.bodies[0], &sim.bodies[1]);
+    let distance = jupiter.pos.norm() + sun.pos.norm();
+    let rate = (GRAVITATIONAL_CONSTANT * (sun.mass + jupiter.mass) / distance.powi(3)).sqrt();
+    let jupiter_angle = jupiter.pos.y.atan2(jupiter.pos.x);
+    let sun_pos = sun.pos;
+    let mut rng = Rng::new(0);
+    for n in 0..500 {
+        let lagrange = if n % 2 == 0 { PI / 3.0 } else { -PI / 3.0 };
+        let angle = jupiter_angle + lagrange + rng.normal() * 0.1;
+        let radius = distance * (1.0 + rng.normal() * 0.01);
+        //The Lagrange points form equilateral triangles with the sun and Jupiter
+        let pos = sun_pos + Vector3::new(angle.cos(), angle.sin(), 0.0) * radius;
+        sim.add_body(
+            Body::particle()
+                .set_position(pos)
+                .set_velocity(Vector3::z().cross(&pos) * rate),
+        );
+    }
+    sim
+}
+
+impl SimState {
+    ///Replaces the simulation by the preset called `name`, keeping the time scaling of the client, and
+    ///notifies the client.
+    ///
+    /// # Error
+    ///
+    /// Fails if no preset has this name.
+    pub fn load_preset(
+        &mut self,
+        name: &str,
+        event_sender: crossbeam_channel::Sender<SimEvent>,
+    ) -> Res<()> {
+        let preset = preset(name)?;
+        *self = preset.build(self.target_time_per_step_s, self.metadata.time_scaling);
+        event_sender
+            .send(SimEvent::Loaded {
+                name: preset.name,
+                bodies: massive_count(&self.bodies),
+            })
+            .unwrap();
+        Ok(())
+    }
+}
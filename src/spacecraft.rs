@@ -0,0 +1,268 @@
+use std::{collections::HashMap, fmt::Display};
+
+use crate::{Body, Res, SimEvent, SimState};
+use na::Vector3;
+
+///Standard gravity in m/s², which converts the specific impulse into the exhaust velocity
+const STANDARD_GRAVITY: f64 = 9.80665;
+///A spacecraft docks with bodies up to this many times its mass, heavier ones swallow it and its schedule
+const DOCKING_MASS_RATIO: f64 = 10.0;
+
+///A change of velocity a spacecraft performs on its own. Times are simulated seconds since the start, directions
+///are given in the inertial frame.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Manoeuvre {
+    ///Instant change of velocity by `delta_v` at `time`, without consuming propellant
+    Impulse { time: f64, delta_v: Vector3<f64> },
+    ///Engine firing from `start` for `duration` seconds with `thrust` in N along `direction`. The propellant is
+    ///used up with the specific impulse `isp` in s, so the burn stops early when the tanks run dry.
+    Burn {
+        start: f64,
+        duration: f64,
+        thrust: f64,
+        isp: f64,
+        direction: Vector3<f64>,
+    },
+}
+
+impl Display for Manoeuvre {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Manoeuvre::Impulse { time, delta_v } => write!(
+                f,
+                "{{\"time\": {}, \"dvx\": {}, \"dvy\": {}, \"dvz\": {}}}",
+                time, delta_v.x, delta_v.y, delta_v.z
+            ),
+            Manoeuvre::Burn {
+                start,
+                duration,
+                thrust,
+                isp,
+                direction,
+            } => write!(
+                f,
+                "{{\"start\": {}, \"duration\": {}, \"thrust\": {}, \"isp\": {}, \"x\": {}, \"y\": {}, \"z\": {}}}",
+                start, duration, thrust, isp, direction.x, direction.y, direction.z
+            ),
+        }
+    }
+}
+
+impl Manoeuvre {
+    ///Parses an impulse of the form `{"time": .., "dvx": .., "dvy": .., "dvz": ..}` or a burn of the form
+    ///`{"start": .., "duration": .., "thrust": .., "isp": .., "x": .., "y": .., "z": ..}`
+    ///
+    /// # Error
+    ///
+    /// Fails if a value is missing or no number.
+    pub fn from_string(manoeuvre_str: &str) -> Res<Manoeuvre> {
+        let mut values = HashMap::new();
+        for kv in manoeuvre_str
+            .trim()
+            .strip_prefix("{")
+            .ok_or("Invalid manoeuvre. Expected a JSON object.")?
+            .strip_suffix("}")
+            .ok_or("Invalid manoeuvre. Expected a JSON object.")?
+            .split(",")
+        {
+            let (k, v) = kv.split_once(":").ok_or(format!(
+                "Invalid manoeuvre. Expected 'key: value', but found {kv}."
+            ))?;
+            values.insert(k.trim().trim_matches('"'), v.trim().parse::<f64>()?);
+        }
+        let get = |key: &str| -> Res<f64> {
+            values
+                .get(key)
+                .copied()
+                .ok_or(format!("Invalid manoeuvre. Missing '{key}'.").into())
+        };
+        if values.contains_key("time") {
+            Ok(Manoeuvre::Impulse {
+                time: get("time")?,
+                delta_v: Vector3::new(get("dvx")?, get("dvy")?, get("dvz")?),
+            })
+        } else {
+            Ok(Manoeuvre::Burn {
+                start: get("start")?,
+                duration: get("duration")?,
+                thrust: get("thrust")?,
+                isp: get("isp")?,
+                direction: Vector3::new(get("x")?, get("y")?, get("z")?),
+            })
+        }
+    }
+
+    ///Simulated time at which the manoeuvre begins
+    pub fn start(&self) -> f64 {
+        match self {
+            Manoeuvre::Impulse { time, .. } => *time,
+            Manoeuvre::Burn { start, .. } => *start,
+        }
+    }
+
+    ///Simulated time at which the manoeuvre is over
+    pub fn end(&self) -> f64 {
+        match self {
+            Manoeuvre::Impulse { time, .. } => *time,
+            Manoeuvre::Burn {
+                start, duration, ..
+            } => start + duration,
+        }
+    }
+}
+
+///What makes a body a spacecraft
+#[derive(Clone, Debug)]
+pub(crate) struct Spacecraft {
+    ///Mass without propellant. The rest of the mass of the body can be burnt
+    pub(crate) dry_mass: f64,
+    ///The pending manoeuvres, ordered by their start
+    pub(crate) schedule: Vec<Manoeuvre>,
+}
+
+impl Spacecraft {
+    ///The spacecraft formed when `first` and `second` merge, if any of them is one and the other is no more than
+    ///`DOCKING_MASS_RATIO` times heavier. The propellant of both stays usable, all other mass becomes dry mass, and
+    ///the pending manoeuvres of both are kept. Impulses keep the momentum they give, so they are scaled down to
+    ///the merged mass.
+    pub(crate) fn merge(first: &Body, second: &Body) -> Option<Spacecraft> {
+        let docks = |craft: &Body, other: &Body| {
+            craft.is_spacecraft() && other.mass <= DOCKING_MASS_RATIO * craft.mass
+        };
+        if !docks(first, second) && !docks(second, first) {
+            return None;
+        }
+        let mass = first.mass + second.mass;
+        let mut schedule: Vec<Manoeuvre> = [first, second]
+            .iter()
+            .flat_map(|body| {
+                body.manoeuvres().iter().map(|manoeuvre| match manoeuvre {
+                    Manoeuvre::Impulse { time, delta_v } => Manoeuvre::Impulse {
+                        time: *time,
+                        delta_v: delta_v * body.mass / mass,
+                    },
+                    burn => burn.clone(),
+                })
+            })
+            .collect();
+        schedule.sort_by(|a, b| a.start().total_cmp(&b.start()));
+        Some(Spacecraft {
+            dry_mass: mass - first.propellant() - second.propellant(),
+            schedule,
+        })
+    }
+}
+
+impl Body {
+    ///A spacecraft carrying `propellant` kg on top of its `dry_mass`. Place it with `set_position` and
+    ///`set_velocity` and plan its manoeuvres with `with_manoeuvre`.
+    pub fn spacecraft(dry_mass: f64, propellant: f64, density: f64) -> Body {
+        let mut body = Body::new(dry_mass + propellant, density);
+        body.spacecraft = Some(Spacecraft {
+            dry_mass,
+            schedule: vec![],
+        });
+        body
+    }
+
+    pub fn is_spacecraft(&self) -> bool {
+        self.spacecraft.is_some()
+    }
+
+    ///Mass left to burn. Zero for other bodies
+    pub fn propellant(&self) -> f64 {
+        self.spacecraft
+            .as_ref()
+            .map_or(0.0, |s| (self.mass - s.dry_mass).max(0.0))
+    }
+
+    ///The pending manoeuvres ordered by their start. Empty for other bodies
+    pub fn manoeuvres(&self) -> &[Manoeuvre] {
+        self.spacecraft.as_ref().map_or(&[], |s| &s.schedule)
+    }
+
+    pub fn with_manoeuvre(mut self, manoeuvre: Manoeuvre) -> Body {
+        self.add_manoeuvre(manoeuvre)
+            .expect("Only spacecraft can perform manoeuvres.");
+        self
+    }
+
+    ///Adds `manoeuvre` to the schedule
+    ///
+    /// # Error
+    ///
+    /// Fails if the body is no spacecraft.
+    pub fn add_manoeuvre(&mut self, manoeuvre: Manoeuvre) -> Res<()> {
+        let spacecraft = self
+            .spacecraft
+            .as_mut()
+            .ok_or("Only spacecraft can perform manoeuvres.")?;
+        let index = spacecraft
+            .schedule
+            .partition_point(|m| m.start() <= manoeuvre.start());
+        spacecraft.schedule.insert(index, manoeuvre);
+        Ok(())
+    }
+
+    ///Performs the part of the scheduled manoeuvres that falls between `time` and `time + dt` as a kick to the
+    ///velocity. Manoeuvres that are over are taken off the schedule. Returns whether any was completed.
+    fn manoeuvre(&mut self, time: f64, dt: f64) -> bool {
+        let Some(spacecraft) = self.spacecraft.as_mut() else {
+            return false;
+        };
+        let end = time + dt;
+        let mut completed = false;
+        for manoeuvre in spacecraft.schedule.iter() {
+            if manoeuvre.start() >= end {
+                break;
+            }
+            match manoeuvre {
+                Manoeuvre::Impulse { delta_v, .. } => self.vel += delta_v,
+                Manoeuvre::Burn {
+                    start,
+                    duration,
+                    thrust,
+                    isp,
+                    direction,
+                } => {
+                    let burn_time = (start + duration).min(end) - start.max(time);
+                    let exhaust_velocity = isp * STANDARD_GRAVITY;
+                    let propellant = (self.mass - spacecraft.dry_mass).max(0.0);
+                    let used = (thrust / exhaust_velocity * burn_time.max(0.0)).min(propellant);
+                    if used > 0.0 && direction.norm() > 0.0 {
+                        //Rocket equation of Tsiolkovsky
+                        let delta_v = exhaust_velocity * (self.mass / (self.mass - used)).ln();
+                        self.vel += direction.normalize() * delta_v;
+                        self.mass -= used;
+                        self.radius = Body::radius_of(self.mass, self.density);
+                    }
+                }
+            }
+        }
+        spacecraft.schedule.retain(|m| {
+            let pending = m.end() >= end;
+            completed |= !pending;
+            pending
+        });
+        completed
+    }
+}
+
+impl SimState {
+    ///Performs the manoeuvres of all spacecraft during the next `dt` simulated seconds and notifies the client
+    ///of the completed ones
+    pub(crate) fn manoeuvre(&mut self, dt: f64, event_sender: crossbeam_channel::Sender<SimEvent>) {
+        let time = self.metadata.time;
+        for (index, body) in self.bodies.iter_mut().enumerate() {
+            if body.manoeuvre(time, dt) {
+                event_sender
+                    .send(SimEvent::Manoeuvre {
+                        index,
+                        remaining: body.manoeuvres().len(),
+                        propellant: body.propellant(),
+                    })
+                    .unwrap();
+            }
+        }
+    }
+}
@@ -4,9 +4,11 @@ use std::{
     fs,
     io::{prelude::*, BufReader},
     net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
 };
 mod threadpool;
-use interstellare_simulation::{Body, Res, SimMetaData};
+use interstellare_simulation::{preset, Res, SimEvent, Snapshot, PRESETS};
+use nalgebra::Vector3;
 use regex::Regex;
 use threadpool::ThreadPool;
 
@@ -21,11 +23,7 @@ const PORT: u16 = 8008;
 fn find_interface() -> Res<Interface> {
     let iface = match get_if_addrs::get_if_addrs()?
         .into_iter()
-        .filter(|i| match i.addr {
-            IfAddr::V4(_) => true,
-            _ => false,
-        })
-        .next()
+        .find(|i| matches!(i.addr, IfAddr::V4(_)))
     {
         Some(i) => i,
         None => {
@@ -37,11 +35,13 @@ fn find_interface() -> Res<Interface> {
 
 /// Spawns a new server listening to the Tcp stream of the first available IPv4Interface.
 ///
-/// Automatically opens a Browser window with the servers address.
+/// Automatically opens a Browser window with the servers address. Queries are answered from
+/// `latest_snapshot`, so they do not take frames away from the event stream.
 pub fn spawn(
     input_sender: crossbeam_channel::Sender<String>,
-    simulation_receiver: crossbeam_channel::Receiver<(Vec<Body>, SimMetaData)>,
-    remove_receiver: crossbeam_channel::Receiver<usize>,
+    simulation_receiver: crossbeam_channel::Receiver<Snapshot>,
+    event_receiver: crossbeam_channel::Receiver<SimEvent>,
+    latest_snapshot: Arc<Mutex<Option<Snapshot>>>,
     mut presentation_mode: bool,
 ) -> Res<()> {
     let ip = find_interface()?.ip();
@@ -78,26 +78,65 @@ pub fn spawn(
         let stream = stream?;
 
         let sr = simulation_receiver.clone();
-        let rr = remove_receiver.clone();
+        let er = event_receiver.clone();
         let is = input_sender.clone();
+        let ls = latest_snapshot.clone();
         pool.execute(|| {
-            match handle_connection(stream, sr, rr, is) {
-                _ => {}
-            };
+            let _ = handle_connection(stream, sr, er, is, ls);
         })?;
     }
 
     Ok(())
 }
 
+/// Formats `positions` as one flat JSON array `[x0, y0, z0, x1, ...]`, which keeps the event small for
+/// thousands of test particles.
+fn flat_positions(positions: &[Vector3<f64>]) -> String {
+    let coordinates: Vec<String> = positions
+        .iter()
+        .flat_map(|p| [p.x, p.y, p.z])
+        .map(|c| c.to_string())
+        .collect();
+    format!("[{}]", coordinates.join(", "))
+}
+
+/// Reads the query `?primary=..&secondary=..` of `location` and returns the Lagrange points of this pair
+/// in the latest snapshot as JSON.
+///
+/// # Error
+///
+/// Fails if an index is missing or invalid, or there is no snapshot yet.
+fn lagrange_points(location: &str, latest_snapshot: &Mutex<Option<Snapshot>>) -> Res<String> {
+    let query: HashMap<&str, &str> = location
+        .split_once('?')
+        .map_or("", |(_, query)| query)
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .collect();
+    let index = |key: &str| -> Res<usize> {
+        Ok(query
+            .get(key)
+            .ok_or(format!("Missing the query parameter '{key}'."))?
+            .parse::<usize>()?)
+    };
+    let (primary, secondary) = (index("primary")?, index("secondary")?);
+    let latest = latest_snapshot
+        .lock()
+        .map_err(|_| "The simulation stopped.")?;
+    let snapshot = latest
+        .as_ref()
+        .ok_or("The simulation has not started yet.")?;
+    Ok(snapshot.lagrange_points(primary, secondary)?.to_string())
+}
+
 /// Sends the simulation data
 ///
 /// Keeps the incoming `stream` open as a event stream, allowing
 /// Javasript to declare it as an Eventsource.
 fn send_simulation(
     mut stream: TcpStream,
-    simulation_receiver: crossbeam_channel::Receiver<(Vec<Body>, SimMetaData)>,
-    remove_receiver: crossbeam_channel::Receiver<usize>,
+    simulation_receiver: crossbeam_channel::Receiver<Snapshot>,
+    event_receiver: crossbeam_channel::Receiver<SimEvent>,
     httpversion: &str,
 ) -> Res<()> {
     let response = format!(
@@ -107,28 +146,38 @@ fn send_simulation(
     stream.flush()?;
     let mut id = 0;
     loop {
-        match remove_receiver.try_recv() {
-            Ok(removed_index) => {
+        match event_receiver.try_recv() {
+            Ok(sim_event) => {
                 id += 1;
                 let event = format!(
-                    "id: {id}\r\nevent: removed\r\ndata: {{\"index\":{:?}}}\r\n\r\n",
-                    removed_index
+                    "id: {id}\r\nevent: {}\r\ndata: {}\r\n\r\n",
+                    sim_event.name(),
+                    sim_event
                 );
                 stream.write_all(event.as_bytes())?;
                 stream.flush()?;
             }
             Err(e) => {
                 if e == crossbeam_channel::TryRecvError::Disconnected {
-                    return Err("Remove stream disconected. Ending simulation".into());
+                    return Err("Event stream disconected. Ending simulation".into());
                 }
             }
         }
         match simulation_receiver.try_recv() {
-            Ok(simulation_state) => {
+            Ok(snapshot) => {
                 id += 1;
                 let event = format!(
-                    "id: {id}\r\nevent: simulation\r\ndata: {{\"simstate\": {:?}, \"metadata\": {}}}\r\n\r\n",
-                    simulation_state.0, simulation_state.1
+                    "id: {id}\r\nevent: simulation\r\ndata: {{\"simstate\": {:?}, \"particles\": {}, \"metadata\": {}, \"report\": {}, \"diagnostics\": {}, \"hierarchy\": {}}}\r\n\r\n",
+                    snapshot.bodies,
+                    flat_positions(&snapshot.particles),
+                    snapshot.metadata,
+                    snapshot.report,
+                    snapshot
+                        .diagnostics
+                        .map_or(String::from("null"), |d| d.to_string()),
+                    snapshot
+                        .hierarchy
+                        .map_or(String::from("null"), |h| h.to_string())
                 );
                 stream.write_all(event.as_bytes())?;
                 stream.flush()?;
@@ -145,9 +194,10 @@ fn send_simulation(
 /// Reads the request from `stream` and sends the corresponding response.
 fn handle_connection(
     mut stream: TcpStream,
-    simulation_receiver: crossbeam_channel::Receiver<(Vec<Body>, SimMetaData)>,
-    remove_receiver: crossbeam_channel::Receiver<usize>,
+    simulation_receiver: crossbeam_channel::Receiver<Snapshot>,
+    event_receiver: crossbeam_channel::Receiver<SimEvent>,
     input_sender: crossbeam_channel::Sender<String>,
+    latest_snapshot: Arc<Mutex<Option<Snapshot>>>,
 ) -> Res<()> {
     let mut buf_reader = BufReader::new(&mut stream);
     //Read the HTTP-Request
@@ -187,7 +237,33 @@ fn handle_connection(
             let filename = match request_location {
                 //Requests to the "/simulation" endpoint, open the SSE connection
                 "/simulation" => {
-                    send_simulation(stream, simulation_receiver, remove_receiver, httpversion)?;
+                    send_simulation(stream, simulation_receiver, event_receiver, httpversion)?;
+                    return Ok(());
+                }
+                //Lists the presets the client can load
+                "/presets" => {
+                    let presets: Vec<String> = PRESETS.iter().map(|p| p.to_string()).collect();
+                    let contents = format!("[{}]", presets.join(", "));
+                    let length = contents.len();
+                    let response = format!(
+                        "{httpversion} 200 OK\r\ncontent-type: application/json\r\nContent-length: {length}\r\n\r\n{contents}"
+                    );
+                    stream.write_all(response.as_bytes())?;
+                    return Ok(());
+                }
+                //The Lagrange points of a pair of bodies, queried as `/lagrange?primary=0&secondary=3`. They are
+                //taken from the latest snapshot, so they match the positions the client draws
+                location if location.starts_with("/lagrange") => {
+                    let (status_line, contents) = match lagrange_points(location, &latest_snapshot)
+                    {
+                        Ok(points) => ("200 OK", points),
+                        Err(e) => ("400 BAD REQUEST", format!("{{\"error\": \"{e}\"}}")),
+                    };
+                    let length = contents.len();
+                    let response = format!(
+                        "{httpversion} {status_line}\r\ncontent-type: application/json\r\nContent-length: {length}\r\n\r\n{contents}"
+                    );
+                    stream.write_all(response.as_bytes())?;
                     return Ok(());
                 }
                 //Other GET-Request get the data of the endpoints file
@@ -237,6 +313,30 @@ fn handle_connection(
                     status_line = "200 OK";
                     String::from("{}")
                 }
+                //Loads the preset given as {"name": "..."}. Unknown presets are rejected before they reach
+                //the simulation
+                "/presets" => {
+                    let name = body
+                        .trim()
+                        .trim_start_matches('{')
+                        .trim_end_matches('}')
+                        .split_once(':')
+                        .map_or("", |(_, name)| name.trim().trim_matches('"'));
+                    match preset(name) {
+                        Ok(preset) => {
+                            input_sender.send(format!(
+                                "{{\"eventType\":\"Preset\",\"body\":\"{}\"}}",
+                                preset.name
+                            ))?;
+                            status_line = "200 OK";
+                            String::from("{}")
+                        }
+                        Err(e) => {
+                            status_line = "400 BAD REQUEST";
+                            format!("{{\"error\": \"{e}\"}}")
+                        }
+                    }
+                }
                 _ => {
                     status_line = "404 NOT FOUND";
                     String::from("{}")
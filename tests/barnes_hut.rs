@@ -0,0 +1,59 @@
+extern crate nalgebra as na;
+use interstellare_simulation::{Body, SimMetaData, SimState};
+use na::Vector3;
+
+///Small linear congruential generator, so the test does not depend on a random crate
+fn uniform(state: &mut u64) -> f64 {
+    *state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    (*state >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[test]
+fn barnes_hut_force_error_is_bounded() {
+    let mut seed = 42;
+    let bodies: Vec<Body> = (0..1000)
+        .map(|_| {
+            let pos = Vector3::new(
+                uniform(&mut seed) - 0.5,
+                uniform(&mut seed) - 0.5,
+                uniform(&mut seed) - 0.5,
+            ) * 1e12;
+            Body::new(1e24 * (0.5 + uniform(&mut seed)), 3000.0).set_position(pos)
+        })
+        .collect();
+
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.metadata.update("{\"solver\":\"direct\"}").unwrap();
+    let direct = sim.accelerations(&bodies);
+    sim.metadata
+        .update("{\"solver\":\"barneshut\",\"opening_angle\":0.5}")
+        .unwrap();
+    let approx = sim.accelerations(&bodies);
+
+    //Near the center of the cloud the forces almost cancel, so the errors are measured relative to the
+    //typical force instead of the force on each body
+    let rms_force =
+        (direct.iter().map(|d| d.norm_squared()).sum::<f64>() / direct.len() as f64).sqrt();
+    let errors: Vec<f64> = direct
+        .iter()
+        .zip(&approx)
+        .map(|(d, a)| (d - a).norm() / rms_force)
+        .collect();
+    let rms = (errors.iter().map(|e| e * e).sum::<f64>() / errors.len() as f64).sqrt();
+    let max = errors.iter().cloned().fold(0.0, f64::max);
+    assert!(rms < 1e-2, "rms relative force error {rms} too large");
+    assert!(max < 5e-2, "max relative force error {max} too large");
+}
+
+#[test]
+fn parsed_metadata_keeps_the_defaults_of_the_simulation() {
+    let parsed = SimMetaData::from_string("{\"solver\": \"barneshut\"}").unwrap();
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.metadata.update("{\"solver\": \"barneshut\"}").unwrap();
+    assert_eq!(parsed.to_string(), sim.metadata.to_string());
+    assert!(parsed
+        .to_string()
+        .contains("\"solver\": \"barneshut\", \"opening_angle\": 0.5"));
+}
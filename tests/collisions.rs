@@ -0,0 +1,110 @@
+extern crate nalgebra as na;
+use interstellare_simulation::{Body, SimEvent, SimState};
+use na::Vector3;
+
+fn momentum(sim: &SimState) -> Vector3<f64> {
+    sim.bodies.iter().map(|b| b.vel() * b.mass()).sum()
+}
+
+fn mass(sim: &SimState) -> f64 {
+    sim.bodies.iter().map(|b| b.mass()).sum()
+}
+
+///Two bodies of about 200 km radius flying head on into each other along x, without gravitation
+fn head_on(collision_policy: &str) -> SimState {
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.bodies = vec![
+        Body::new(1e20, 3000.0)
+            .set_position(Vector3::new(-1e6, 0.0, 0.0))
+            .set_velocity(Vector3::new(2e3, 0.0, 0.0)),
+        Body::new(3e20, 3000.0)
+            .set_position(Vector3::new(1e6, 0.0, 0.0))
+            .set_velocity(Vector3::new(-1e3, 10.0, 0.0)),
+    ];
+    sim.metadata
+        .update(&format!(
+            "{{\"interaction_constant\": 0, \"pacing\": \"fast\", \"fixed_dt\": 10, \"collision_policy\": \"{collision_policy}\", \"restitution\": 0.5, \"fragments\": 4, \"min_fragment_mass\": 1}}"
+        ))
+        .unwrap();
+    sim
+}
+
+///Steps until the first collision event and returns the events so far
+fn collide(sim: &mut SimState) -> Vec<SimEvent> {
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    for _ in 0..100 {
+        sim.fixed_step(sender.clone());
+        if !receiver.is_empty() {
+            break;
+        }
+    }
+    receiver.try_iter().collect()
+}
+
+#[test]
+fn merging_conserves_mass_momentum_and_volume() {
+    let mut sim = head_on("merge");
+    let (before_mass, before_momentum) = (mass(&sim), momentum(&sim));
+    let volume: f64 = sim.bodies.iter().map(|b| b.radius().powi(3)).sum();
+    let events = collide(&mut sim);
+    assert_eq!(sim.bodies.len(), 1);
+    assert!(matches!(
+        events[..],
+        [SimEvent::Collision {
+            survivor: 0,
+            removed: 1,
+            ..
+        }]
+    ));
+    assert_eq!(sim.bodies[0].mass(), before_mass);
+    assert!((momentum(&sim) - before_momentum).norm() < 1e-12 * before_momentum.norm());
+    assert!((sim.bodies[0].radius().powi(3) - volume).abs() < 1e-12 * volume);
+}
+
+#[test]
+fn bounces_follow_the_restitution() {
+    let mut sim = head_on("bounce");
+    let before_momentum = momentum(&sim);
+    let events = collide(&mut sim);
+    assert_eq!(sim.bodies.len(), 2);
+    assert_eq!(events[0].name(), "bounce");
+    let (first, second) = (&sim.bodies[0], &sim.bodies[1]);
+    //Along the line of centres the bodies separate with half the speed they approached with. The bodies are
+    //pushed apart along it, so it stays the same
+    let approach = Vector3::new(2e3, 0.0, 0.0) - Vector3::new(-1e3, 10.0, 0.0);
+    let normal = (second.pos() - first.pos()).normalize();
+    let separation = (second.vel() - first.vel()).dot(&normal);
+    assert!(
+        (separation - 0.5 * approach.dot(&normal)).abs() < 1e-6,
+        "{separation}"
+    );
+    assert!((momentum(&sim) - before_momentum).norm() < 1e-12 * before_momentum.norm());
+}
+
+#[test]
+fn fragments_conserve_mass_and_momentum() {
+    let mut sim = head_on("fragment");
+    let (before_mass, before_momentum) = (mass(&sim), momentum(&sim));
+    let events = collide(&mut sim);
+    assert_eq!(sim.bodies.len(), 4);
+    assert_eq!(
+        events[0].to_string(),
+        "{\"first\": 0, \"second\": 1, \"first_new\": 2, \"fragments\": 4}"
+    );
+    assert!((mass(&sim) - before_mass).abs() < 1e-12 * before_mass);
+    assert!((momentum(&sim) - before_momentum).norm() < 1e-9 * before_momentum.norm());
+    for body in &sim.bodies {
+        assert_eq!(body.mass(), before_mass / 4.0);
+    }
+}
+
+#[test]
+fn removed_bodies_are_reported_from_the_back() {
+    let mut sim = head_on("remove");
+    let events = collide(&mut sim);
+    assert!(sim.bodies.is_empty());
+    assert!(matches!(
+        events[..],
+        [SimEvent::Removed(1), SimEvent::Removed(0)]
+    ));
+}
@@ -0,0 +1,65 @@
+extern crate nalgebra as na;
+use interstellare_simulation::{Body, SimState, GRAVITATIONAL_CONSTANT};
+use na::Vector3;
+
+#[test]
+fn diagnostics_and_hierarchy_are_measured_every_interval() {
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.metadata
+        .update("{\"pacing\": \"fast\", \"analysis_interval\": 2}")
+        .unwrap();
+    let (sender, _receiver) = crossbeam_channel::unbounded();
+    let mut energies = vec![];
+    for _ in 0..3 {
+        energies.push(sim.snapshot().diagnostics.unwrap().kinetic_energy);
+        sim.fixed_step(sender.clone());
+    }
+    assert_eq!(energies[0], energies[1]);
+    assert_ne!(energies[1], energies[2]);
+
+    let mut meta = sim.metadata.clone();
+    meta.update("{\"analysis_interval\": 0}").unwrap();
+    sim.handle_meta_input(meta);
+    let snapshot = sim.snapshot();
+    assert!(snapshot.diagnostics.is_none());
+    assert!(snapshot.hierarchy.is_none());
+}
+
+#[test]
+fn diagnostics_are_measured_every_frame_by_default() {
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.metadata.update("{\"pacing\": \"fast\"}").unwrap();
+    let (sender, _receiver) = crossbeam_channel::unbounded();
+    let first = sim.snapshot().diagnostics.unwrap().kinetic_energy;
+    sim.fixed_step(sender);
+    assert_ne!(sim.snapshot().diagnostics.unwrap().kinetic_energy, first);
+}
+
+#[test]
+fn circular_orbits_do_not_drift() {
+    let (sun, earth, distance) = (1.98847e30, 5.9722e24, 1.496e11);
+    let total = sun + earth;
+    let speed = (GRAVITATIONAL_CONSTANT * total / distance).sqrt();
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.bodies = vec![
+        Body::new(sun, 1410.0)
+            .set_position(Vector3::new(-distance * earth / total, 0.0, 0.0))
+            .set_velocity(Vector3::new(0.0, -speed * earth / total, 0.0)),
+        Body::new(earth, 5514.0)
+            .set_position(Vector3::new(distance * sun / total, 0.0, 0.0))
+            .set_velocity(Vector3::new(0.0, speed * sun / total, 0.0)),
+    ];
+    sim.metadata
+        .update("{\"pacing\": \"fast\", \"fixed_dt\": 3600}")
+        .unwrap();
+    let (sender, _receiver) = crossbeam_channel::unbounded();
+    for _ in 0..2000 {
+        sim.fixed_step(sender.clone());
+    }
+    let diagnostics = sim.diagnostics();
+    let bound = -GRAVITATIONAL_CONSTANT * sun * earth / (2.0 * distance);
+    assert!((diagnostics.total_energy() - bound).abs() < 1e-9 * bound.abs());
+    assert!(diagnostics.energy_drift.abs() < 1e-9);
+    assert!(diagnostics.momentum_drift < 1e-12);
+    assert!(diagnostics.angular_momentum_drift < 1e-12);
+}
@@ -0,0 +1,83 @@
+extern crate nalgebra as na;
+use interstellare_simulation::{Body, SimEvent, SimState};
+use na::Vector3;
+
+fn run(sim: &mut SimState, steps: usize) -> Vec<SimEvent> {
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    for _ in 0..steps {
+        sim.fixed_step(sender.clone());
+    }
+    receiver.try_iter().collect()
+}
+
+#[test]
+fn flybys_are_reported_once_at_the_closest_approach() {
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.bodies = vec![
+        Body::new(1e20, 3000.0),
+        Body::new(1e20, 3000.0)
+            .set_position(Vector3::new(-1e8, 1e7, 0.0))
+            .set_velocity(Vector3::new(1e4, 0.0, 0.0)),
+    ];
+    //Without gravitation both move on straight lines, so the closest approach is known exactly
+    sim.metadata
+        .update("{\"interaction_constant\": 0, \"pacing\": \"fast\", \"fixed_dt\": 300, \"encounter_distance\": 5e7}")
+        .unwrap();
+    let events = run(&mut sim, 100);
+    assert_eq!(events.len(), 1);
+    match events[0] {
+        SimEvent::Encounter {
+            first,
+            second,
+            time,
+            distance,
+            speed,
+        } => {
+            assert_eq!((first, second), (0, 1));
+            assert!((time - 1e4).abs() < 1e-6);
+            assert!((distance - 1e7).abs() < 1e-3);
+            assert!((speed - 1e4).abs() < 1e-9);
+        }
+        _ => panic!("Expected an encounter, but found {:?}", events[0]),
+    }
+    assert_eq!(events[0].name(), "encounter");
+}
+
+#[test]
+fn only_unbound_bodies_escape() {
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.bodies = vec![
+        Body::sun(),
+        //Bound, but its apoapsis lies beyond the escape radius
+        Body::new(1e20, 3000.0)
+            .set_position(Vector3::new(1e10, 0.0, 0.0))
+            .set_velocity(Vector3::new(0.0, 1.5e5, 0.0)),
+        Body::new(1e20, 3000.0)
+            .set_position(Vector3::new(-1e10, 0.0, 0.0))
+            .set_velocity(Vector3::new(-2e5, 0.0, 0.0)),
+    ];
+    sim.metadata
+        .update("{\"pacing\": \"fast\", \"fixed_dt\": 3600, \"escape_radius\": 2e10}")
+        .unwrap();
+    let events = run(&mut sim, 300);
+    let escapes: Vec<String> = events
+        .iter()
+        .filter(|e| e.name() == "escape")
+        .map(|e| e.to_string())
+        .collect();
+    assert_eq!(escapes.len(), 1);
+    assert!(escapes[0].starts_with("{\"index\": 2, \"time\": "));
+}
+
+#[test]
+fn detection_is_off_by_default() {
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.metadata
+        .update("{\"pacing\": \"fast\", \"fixed_dt\": 3600}")
+        .unwrap();
+    assert!(sim
+        .metadata
+        .to_string()
+        .contains("\"encounter_distance\": 0, \"escape_radius\": 0"));
+    assert!(run(&mut sim, 100).is_empty());
+}
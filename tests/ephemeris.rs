@@ -0,0 +1,62 @@
+use interstellare_simulation::{calendar_date, julian_date, solar_system, SimState};
+
+const AU: f64 = 149597870700.0;
+
+#[test]
+fn earth_is_in_place_at_j2000() {
+    let jd = julian_date("2000-01-01T12:00:00").unwrap();
+    assert_eq!(jd, 2451545.0);
+    let bodies = solar_system(jd).unwrap();
+    let position = bodies[3].pos() / AU;
+    let (x, y) = (position.x, position.y);
+    assert!(
+        (x + 0.177).abs() < 0.01 && (y - 0.967).abs() < 0.01,
+        "{x} {y}"
+    );
+}
+
+#[test]
+fn calendar_dates_survive_the_round_trip() {
+    for date in [
+        "2000-01-01T12:00:00",
+        "1969-07-20T20:17:40",
+        "2024-02-29T00:00:00",
+        "1600-03-01T06:30:15",
+    ] {
+        assert_eq!(calendar_date(julian_date(date).unwrap()), date);
+    }
+    assert_eq!(
+        calendar_date(julian_date("2031-12-31").unwrap()),
+        "2031-12-31T00:00:00"
+    );
+    assert!(julian_date("2023-13-01").is_err());
+}
+
+#[test]
+fn the_date_advances_with_the_simulated_time() {
+    let mut sim = SimState::at_epoch("2024-01-01", 0.01, 1.0).unwrap();
+    sim.metadata
+        .update("{\"pacing\": \"fast\", \"fixed_dt\": 3600}")
+        .unwrap();
+    let (sender, _receiver) = crossbeam_channel::unbounded();
+    for _ in 0..36 {
+        sim.fixed_step(sender.clone());
+    }
+    assert_eq!(sim.time(), 36.0 * 3600.0);
+    assert_eq!(sim.date().unwrap(), "2024-01-02T12:00:00");
+    assert!(sim
+        .metadata
+        .to_string()
+        .contains("\"time\": 129600, \"epoch\": \"2024-01-01T00:00:00\""));
+
+    //The client can move the epoch without touching the elapsed time
+    let mut meta = sim.metadata.clone();
+    meta.update("{\"epoch\": \"1999-12-31T12:00:00\"}").unwrap();
+    sim.handle_meta_input(meta);
+    assert_eq!(sim.date().unwrap(), "2000-01-02T00:00:00");
+    let mut meta = sim.metadata.clone();
+    meta.update("{\"epoch\": null}").unwrap();
+    sim.handle_meta_input(meta);
+    assert_eq!(sim.date(), None);
+    assert_eq!(sim.time(), 129600.0);
+}
@@ -0,0 +1,169 @@
+extern crate nalgebra as na;
+use interstellare_simulation::{AccelerationPolicy, Body, SimEvent, SimState};
+use na::Vector3;
+
+///The sun and a body at rest 1e10 m away, which is pulled with about 1.33 m/s²
+fn close_to_the_sun(policy: &str) -> SimState {
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.bodies = vec![
+        Body::sun(),
+        Body::new(1e20, 3000.0).set_position(Vector3::new(1e10, 0.0, 0.0)),
+    ];
+    sim.metadata
+        .update(&format!(
+            "{{\"pacing\": \"fast\", \"fixed_dt\": 60, \"max_acceleration\": 1, \"acceleration_policy\": \"{policy}\"}}"
+        ))
+        .unwrap();
+    sim
+}
+
+fn step(sim: &mut SimState) -> Vec<SimEvent> {
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    sim.fixed_step(sender);
+    receiver.try_iter().collect()
+}
+
+#[test]
+fn extreme_bodies_are_removed_by_default() {
+    let sim = SimState::new(0.01, 1.0);
+    assert_eq!(sim.metadata.max_acceleration(), 1e6);
+    assert_eq!(
+        sim.metadata.acceleration_policy(),
+        AccelerationPolicy::Remove
+    );
+    let mut sim = close_to_the_sun("remove");
+    let events = step(&mut sim);
+    assert_eq!(sim.bodies.len(), 1);
+    assert_eq!(events[0].name(), "extreme_acceleration");
+    match events[0] {
+        SimEvent::ExtremeAcceleration {
+            index,
+            acceleration,
+            policy,
+        } => {
+            assert_eq!(index, 1);
+            assert!((acceleration - 1.327).abs() < 1e-2, "{acceleration}");
+            assert_eq!(policy, AccelerationPolicy::Remove);
+        }
+        _ => panic!(
+            "Expected an extreme acceleration, but found {:?}",
+            events[0]
+        ),
+    }
+    assert!(matches!(events[1], SimEvent::Removed(1)));
+}
+
+#[test]
+fn clamped_and_reduced_steps_keep_the_body() {
+    let mut clamped = close_to_the_sun("clamp");
+    let events = step(&mut clamped);
+    assert_eq!(clamped.bodies.len(), 2);
+    assert_eq!(events.len(), 1);
+    assert!(
+        clamped.bodies[1].vel().x >= -60.0 - 1e-9,
+        "{}",
+        clamped.bodies[1].vel().x
+    );
+
+    let mut reduced = close_to_the_sun("reduce_step");
+    step(&mut reduced);
+    assert_eq!(reduced.bodies.len(), 2);
+    assert_eq!(reduced.report.substeps, 2);
+    //Only the step size shrinks, the body still feels the full pull
+    assert!(
+        (reduced.bodies[1].vel().x + 1.327 * 60.0).abs() < 0.1,
+        "{}",
+        reduced.bodies[1].vel().x
+    );
+}
+
+#[test]
+fn merging_and_pausing_are_reported_to_the_client() {
+    let mut merged = close_to_the_sun("merge");
+    let events = step(&mut merged);
+    assert_eq!(merged.bodies.len(), 1);
+    assert!(matches!(
+        events[1],
+        SimEvent::Collision {
+            survivor: 0,
+            removed: 1,
+            mass,
+            ..
+        } if mass == 1.98847e30 + 1e20
+    ));
+
+    let mut paused = close_to_the_sun("pause");
+    let events = step(&mut paused);
+    assert_eq!(events[0].name(), "extreme_acceleration");
+    assert!(paused.is_paused());
+    step(&mut paused);
+    assert_eq!(paused.time(), 0.0);
+    //Any change by the client resumes the simulation
+    let mut meta = paused.metadata.clone();
+    meta.update("{\"acceleration_policy\": \"clamp\"}").unwrap();
+    paused.handle_meta_input(meta);
+    assert!(!paused.is_paused());
+    step(&mut paused);
+    assert_eq!(paused.time(), 60.0);
+}
+
+///A body falling from rest towards the sun, pulled with about 133 m/s²
+fn falling(integrator: &str, fixed_dt: f64, max_acceleration: f64) -> SimState {
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.bodies = vec![
+        Body::sun(),
+        Body::new(1e20, 3000.0).set_position(Vector3::new(1e9, 0.0, 0.0)),
+    ];
+    sim.metadata
+        .update(&format!(
+            "{{\"pacing\": \"fast\", \"integrator\": \"{integrator}\", \"fixed_dt\": {fixed_dt}, \"max_acceleration\": {max_acceleration}, \"acceleration_policy\": \"reduce_step\"}}"
+        ))
+        .unwrap();
+    sim
+}
+
+#[test]
+fn reduced_steps_follow_the_smaller_steps() {
+    for integrator in ["euler", "verlet", "rk4", "yoshida"] {
+        let mut reduced = falling(integrator, 600.0, 1.0);
+        step(&mut reduced);
+        assert_eq!(reduced.report.substeps, 133, "{integrator}");
+
+        let mut single = falling(integrator, 600.0, 1e6);
+        step(&mut single);
+        //Even rk4 deviates noticeably in a single step of this size
+        assert!(
+            (reduced.bodies[1].vel().x - single.bodies[1].vel().x).abs()
+                > 1e-6 * single.bodies[1].vel().x.abs(),
+            "{integrator}: {} {}",
+            reduced.bodies[1].vel().x,
+            single.bodies[1].vel().x
+        );
+
+        let mut small = falling(integrator, 600.0 / 133.0, 1e6);
+        for _ in 0..133 {
+            step(&mut small);
+        }
+        let expected = small.bodies[1].vel().x;
+        assert!(
+            (reduced.bodies[1].vel().x - expected).abs() < 1e-9 * expected.abs(),
+            "{integrator}: {} {expected}",
+            reduced.bodies[1].vel().x
+        );
+    }
+}
+
+#[test]
+fn crossings_are_reported_once() {
+    let mut clamped = close_to_the_sun("clamp");
+    let events: Vec<SimEvent> = (0..5).flat_map(|_| step(&mut clamped)).collect();
+    assert_eq!(events.len(), 1);
+
+    //The client only knows massive bodies by index, so removed particles are not reported as removed
+    let mut sim = close_to_the_sun("remove");
+    sim.bodies[1] = Body::particle().set_position(Vector3::new(1e10, 0.0, 0.0));
+    let events = step(&mut sim);
+    assert_eq!(sim.bodies.len(), 1);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].name(), "extreme_acceleration");
+}
@@ -0,0 +1,157 @@
+extern crate nalgebra as na;
+use std::sync::Arc;
+
+use interstellare_simulation::{
+    AtmosphericDrag, Body, ConstantField, InputEvent, PairwiseLaw, RadiationPressure, SimState,
+    GRAVITATIONAL_CONSTANT,
+};
+use na::Vector3;
+
+///A satellite of one tonne with a radius of one metre on a circular orbit at `height` over the earth
+fn earth_and_satellite(height: f64) -> SimState {
+    let mut sim = SimState::new(0.01, 1.0);
+    let earth = Body::new(5.9722e24, 5514.0);
+    let distance = Body::radius_of(5.9722e24, 5514.0) + height;
+    let speed = (GRAVITATIONAL_CONSTANT * 5.9722e24 / distance).sqrt();
+    let satellite = Body::new(1000.0, 1000.0 / (4.0 / 3.0 * std::f64::consts::PI))
+        .set_position(Vector3::new(distance, 0.0, 0.0))
+        .set_velocity(Vector3::new(0.0, speed, 0.0));
+    sim.bodies = vec![earth, satellite];
+    sim.metadata
+        .update("{\"pacing\": \"fast\", \"fixed_dt\": 1}")
+        .unwrap();
+    sim
+}
+
+fn semi_major_axis(sim: &SimState) -> f64 {
+    sim.orbital_elements(1, 0).semi_major_axis
+}
+
+#[test]
+fn constant_field_accelerates_every_body_alike() {
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.bodies = vec![
+        Body::new(1.0, 1000.0),
+        Body::new(1.0, 1000.0).set_position(Vector3::new(1e9, 0.0, 0.0)),
+        Body::particle().set_position(Vector3::new(-1e9, 0.0, 0.0)),
+    ];
+    sim.metadata
+        .update("{\"interaction_constant\": 0}")
+        .unwrap();
+    sim.add_force(Box::new(ConstantField {
+        field: Vector3::new(0.0, 2.0, 0.0),
+    }));
+    for acc in sim.accelerations(&sim.bodies) {
+        assert_eq!(acc, Vector3::new(0.0, 2.0, 0.0));
+    }
+}
+
+#[test]
+fn parameters_are_reported_and_editable_in_the_metadata() {
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.add_force(Box::new(RadiationPressure::sun()));
+    let meta = sim.metadata.to_string();
+    assert!(meta.ends_with(
+        ", \"radiation_pressure.source\": 0, \"radiation_pressure.luminosity\": 382800000000000000000000000, \"radiation_pressure.efficiency\": 1}"
+    ));
+    sim.metadata
+        .update("{\"radiation_pressure.efficiency\": 2}")
+        .unwrap();
+    assert!(sim
+        .metadata
+        .to_string()
+        .contains("\"radiation_pressure.efficiency\": 2"));
+    assert!(sim
+        .metadata
+        .update("{\"radiation_pressure.albedo\": 2}")
+        .is_err());
+    assert!(sim.metadata.update("{\"drag.scale_height\": 2}").is_err());
+
+    //Registering a force of the same name replaces it
+    sim.add_force(Box::new(RadiationPressure::sun()));
+    assert_eq!(sim.forces().len(), 1);
+    assert!(sim.remove_force("radiation_pressure"));
+    assert!(!sim.metadata.to_string().contains("radiation_pressure"));
+}
+
+#[test]
+fn drag_lowers_orbits_inside_the_atmosphere() {
+    let (sender, _receiver) = crossbeam_channel::unbounded();
+    let mut free = earth_and_satellite(150e3);
+    let mut dragged = earth_and_satellite(150e3);
+    dragged.add_force(Box::new(AtmosphericDrag::earth(0)));
+    for _ in 0..3000 {
+        free.fixed_step(sender.clone());
+        dragged.fixed_step(sender.clone());
+    }
+    assert!(semi_major_axis(&dragged) < semi_major_axis(&free) - 100.0);
+}
+
+#[test]
+fn radiation_pressure_pushes_away_from_the_source() {
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.bodies = vec![
+        Body::sun(),
+        //A grain of dust with a radius of 0.1 µm
+        Body::new(4.19e-18, 1000.0).set_position(Vector3::new(1.496e11, 0.0, 0.0)),
+    ];
+    sim.add_force(Box::new(RadiationPressure::sun()));
+    let accs = sim.accelerations(&sim.bodies);
+    //For grains this small the light outweighs the gravitation of the sun
+    assert!(accs[1].x > 0.0);
+    assert_eq!(accs[0], sim.accelerations(&sim.bodies)[0]);
+}
+
+#[test]
+fn pairwise_laws_act_between_massive_bodies() {
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.bodies = vec![
+        Body::new(1e20, 1000.0),
+        Body::new(1e20, 1000.0).set_position(Vector3::new(1e8, 0.0, 0.0)),
+    ];
+    //An inverse square repulsion of the same strength cancels gravitation
+    sim.add_force(Box::new(PairwiseLaw {
+        name: String::from("repulsion"),
+        strength: -GRAVITATIONAL_CONSTANT,
+        law: Arc::new(|distance| distance.powi(-2)),
+    }));
+    for acc in sim.accelerations(&sim.bodies) {
+        assert!(acc.norm() < 1e-20);
+    }
+    sim.metadata.update("{\"repulsion.strength\": 0}").unwrap();
+    assert!(sim.accelerations(&sim.bodies)[0].x > 0.0);
+}
+
+#[test]
+fn index_parameters_follow_their_bodies() {
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.add_force(Box::new(RadiationPressure::sun()));
+    sim.add_force(Box::new(AtmosphericDrag::earth(3)));
+    let parameter = |sim: &SimState, name: &str| {
+        sim.forces()
+            .iter()
+            .flat_map(|force| force.parameters())
+            .find(|(n, _)| *n == name)
+            .unwrap()
+            .1
+    };
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    sim.handle_input(InputEvent::Remove(1), None, sender.clone())
+        .unwrap();
+    assert_eq!(parameter(&sim, "planet"), 2.0);
+    assert_eq!(parameter(&sim, "source"), 0.0);
+    assert_eq!(receiver.try_iter().count(), 1);
+
+    //Without its body a parameter falls back to the first one, which the client is told about
+    sim.handle_input(InputEvent::Remove(2), None, sender)
+        .unwrap();
+    assert_eq!(parameter(&sim, "planet"), 0.0);
+    let events: Vec<String> = receiver.try_iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        events,
+        vec![
+            "{\"index\": 2}",
+            "{\"parameter\": \"atmospheric_drag.planet\"}"
+        ]
+    );
+}
@@ -0,0 +1,35 @@
+use interstellare_simulation::{
+    plummer, Diagnostics, Generator, SimState, Softening, GRAVITATIONAL_CONSTANT,
+};
+
+const AU: f64 = 149597870700.0;
+
+#[test]
+fn plummer_sphere_starts_in_virial_equilibrium() {
+    let bodies = plummer(2000, 1e33, 20.0 * AU, 7);
+    let diagnostics = Diagnostics::measure(&bodies, GRAVITATIONAL_CONSTANT, Softening::None, 0.0);
+    let virial_ratio = 2.0 * diagnostics.kinetic_energy / -diagnostics.potential_energy;
+    assert!(
+        (virial_ratio - 1.0).abs() < 0.1,
+        "virial ratio {virial_ratio}"
+    );
+}
+
+#[test]
+fn generators_are_reproducible_from_their_seed() {
+    for generator in [
+        "asteroid_belt",
+        "plummer",
+        "disc_galaxy",
+        "protoplanetary_disc",
+    ] {
+        let generator = Generator::from_string(generator).unwrap();
+        let build = |seed| {
+            let mut sim = generator.build(SimState::new(0.01, 1.0), seed);
+            let particles = sim.snapshot().particles;
+            format!("{:?} {:?}", sim.bodies, particles)
+        };
+        assert_eq!(build(3), build(3), "{generator}");
+        assert_ne!(build(3), build(4), "{generator}");
+    }
+}
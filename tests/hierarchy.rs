@@ -0,0 +1,66 @@
+extern crate nalgebra as na;
+use interstellare_simulation::{planetary_moons, Body, SimState};
+use na::Vector3;
+
+const YEAR: f64 = 365.25 * 86400.0;
+
+#[test]
+fn the_moon_orbits_the_earth_and_the_planets_the_sun() {
+    let mut sim = SimState::new(0.01, 1.0);
+    let hierarchy = sim.snapshot().hierarchy.unwrap();
+    assert_eq!(hierarchy.orbits.len(), 10);
+    assert_eq!(hierarchy.parent(0), None);
+    assert_eq!(hierarchy.parent(4), Some(3));
+    assert_eq!(hierarchy.children(0), vec![1, 2, 3, 5, 6, 7, 8, 9]);
+    //The standard earth starts a bit too fast for its distance, so its orbit is slightly larger than the real one
+    let earth = hierarchy.orbits[3].unwrap();
+    assert!((earth.period - YEAR).abs() < 0.1 * YEAR, "{earth:?}");
+    let moon = hierarchy.orbits[4].unwrap();
+    assert!(moon.period > 20.0 * 86400.0 && moon.period < 35.0 * 86400.0);
+    assert!(hierarchy.to_string().starts_with("[null, {\"parent\": 0, "));
+}
+
+#[test]
+fn moons_of_a_planet_without_sun() {
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.bodies = planetary_moons("jupiter", 2451545.0).unwrap();
+    let hierarchy = sim.hierarchy();
+    assert_eq!(hierarchy.parent(0), None);
+    for orbit in &hierarchy.orbits[1..] {
+        let orbit = orbit.unwrap();
+        assert_eq!(orbit.parent, 0);
+        assert!(orbit.eccentricity < 0.1 && orbit.period < 20.0 * 86400.0);
+    }
+}
+
+#[test]
+fn unbound_and_distant_bodies_are_roots() {
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.bodies = vec![
+        Body::new(5.9722e24, 5514.0),
+        //Far faster than the escape velocity
+        Body::new(1e20, 3000.0)
+            .set_position(Vector3::new(1e8, 0.0, 0.0))
+            .set_velocity(Vector3::new(0.0, 1e4, 0.0)),
+        //Slow enough to be bound
+        Body::new(1e20, 3000.0)
+            .set_position(Vector3::new(-1e8, 0.0, 0.0))
+            .set_velocity(Vector3::new(0.0, 2e3, 0.0)),
+    ];
+    let hierarchy = sim.hierarchy();
+    assert_eq!(hierarchy.parent(1), None);
+    assert_eq!(hierarchy.parent(2), Some(0));
+    assert_eq!(hierarchy.to_string().matches("null").count(), 2);
+}
+
+#[test]
+fn the_hierarchy_is_detected_every_frame_by_default() {
+    let mut sim = SimState::new(0.01, 1.0);
+    assert_eq!(sim.snapshot().hierarchy.unwrap().parent(4), Some(3));
+    //Flinging the moon away unbinds it from the earth
+    let moon = sim.bodies[4]
+        .clone()
+        .set_velocity(Vector3::new(0.0, 0.0, 1e6));
+    sim.bodies[4] = moon;
+    assert_eq!(sim.snapshot().hierarchy.unwrap().parent(4), None);
+}
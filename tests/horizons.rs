@@ -0,0 +1,126 @@
+extern crate nalgebra as na;
+use interstellare_simulation::{physical_properties, HorizonsRecord, SimState};
+use na::Vector3;
+use std::{fs, path::PathBuf};
+
+const TEXT_EXPORT: &str = "\
+*******************************************************************************
+Ephemeris / API_USER Mon Jan  1 00:00:00 2024 Pasadena, USA      / Horizons
+*******************************************************************************
+Target body name: Earth (399)                     {source: DE441}
+Center body name: Sun (10)                        {source: DE441}
+*******************************************************************************
+Output units    : KM-S
+*******************************************************************************
+$$SOE
+2460310.500000000 = A.D. 2024-Jan-01 00:00:00.0000 TDB 
+ X =-2.481198069465988E+07 Y = 1.449937394159862E+08 Z =-8.210850421313377E+03
+ VX=-2.984228378144804E+01 VY=-5.126754387997897E+00 VZ= 1.031213746375155E-03
+ LT= 4.906655071458710E+02 RG= 1.470975390946829E+08 RR=-1.609040126862044E-02
+2460311.500000000 = A.D. 2024-Jan-02 00:00:00.0000 TDB 
+ X =-2.738406826377893E+07 Y = 1.445336768035744E+08 Z =-8.107264005459845E+03
+ VX=-2.968649138880406E+01 VY=-5.521424958040566E+00 VZ= 1.365396436028271E-03
+ LT= 4.906708013432924E+02 RG= 1.470991262616068E+08 RR= 1.997773431616620E-02
+$$EOE
+*******************************************************************************
+";
+
+const CSV_EXPORT: &str = "\
+Target body name: Earth (399)                     {source: DE441}
+Output units    : AU-D
+*******************************************************************************
+            JDTDB,            Calendar Date (TDB),                      X,                      Y,                      Z,                     VX,                     VY,                     VZ,
+**************************************************************************************************************************************************************************
+$$SOE
+2460310.500000000, A.D. 2024-Jan-01 00:00:00.0000, -1.658578466294967E-01,  9.692232833096482E-01, -5.488614498918384E-05, -1.723536108269695E-02, -2.960948421594201E-03,  5.955757743737285E-07,
+$$EOE
+";
+
+#[test]
+fn text_and_csv_exports_agree_in_si_units() {
+    let text = HorizonsRecord::from_string(TEXT_EXPORT).unwrap();
+    assert_eq!(text.len(), 2);
+    assert_eq!(text[0].name(), "earth");
+    assert_eq!(text[0].id(), Some("399"));
+    assert_eq!(text[1].julian_date, 2460311.5);
+    let expected = Vector3::new(
+        -2.984228378144804E+04,
+        -5.126754387997897E+03,
+        1.031213746375155E+00,
+    );
+    assert!((text[0].vel - expected).norm() < 1e-9 * expected.norm());
+
+    let csv = HorizonsRecord::from_string(CSV_EXPORT).unwrap();
+    assert_eq!(csv.len(), 1);
+    assert_eq!(csv[0].julian_date, text[0].julian_date);
+    assert!((csv[0].pos - text[0].pos).norm() < 1e-6 * text[0].pos.norm());
+    assert!((csv[0].vel - text[0].vel).norm() < 1e-6 * text[0].vel.norm());
+}
+
+#[test]
+fn rows_missing_a_component_are_rejected() {
+    let broken = TEXT_EXPORT.replace(" VZ= 1.031213746375155E-03", "");
+    assert!(HorizonsRecord::from_string(&broken).is_err());
+}
+
+///Writes `content` to a file named `name` in a temporary directory of `test`
+fn temporary_file(test: &str, name: &str, content: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("horizons-{test}-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    fs::write(&path, content).unwrap();
+    path
+}
+
+///The text export with the moon as target, shifted by `days`
+fn moon_export(days: f64) -> String {
+    TEXT_EXPORT
+        .replace("Earth (399)", "Moon (301)")
+        .replace("2460310.500000000", &format!("{:.9}", 2460310.5 + days))
+}
+
+const PROPERTIES: &str = "\
+# name or id, mass in kg, density in kg/m^3
+Earth 5.9722e24 5514
+301   7.342e22  3344
+";
+
+#[test]
+fn properties_are_found_by_name_and_by_id() {
+    let properties = physical_properties(PROPERTIES).unwrap();
+    assert_eq!(properties["earth"], (5.9722e24, 5514.0));
+    assert_eq!(properties["301"], (7.342e22, 3344.0));
+    assert!(physical_properties("earth 5.9722e24").is_err());
+
+    let test = "lookup";
+    let earth = temporary_file(test, "earth.txt", TEXT_EXPORT);
+    let moon = temporary_file(test, "moon.txt", &moon_export(0.0));
+    let table = temporary_file(test, "properties.txt", PROPERTIES);
+    let sim = SimState::from_horizons(&[earth.clone(), moon], &table, 0.01, 1.0).unwrap();
+    assert_eq!(sim.bodies.len(), 2);
+    assert_eq!(sim.bodies[0].mass(), 5.9722e24);
+    assert_eq!(sim.bodies[1].mass(), 7.342e22);
+    assert_eq!(sim.bodies[1].pos(), sim.bodies[0].pos());
+    //The exports start at midnight TDB, which is 69.184 s earlier in UTC
+    assert_eq!(sim.date().unwrap(), "2023-12-31T23:58:51");
+
+    let table = temporary_file(test, "earth_only.txt", "earth 5.9722e24 5514");
+    let moon = temporary_file(test, "moon.txt", &moon_export(0.0));
+    assert!(SimState::from_horizons(&[earth, moon], table, 0.01, 1.0).is_err());
+}
+
+#[test]
+fn exports_must_start_at_the_same_epoch() {
+    let test = "epoch";
+    let earth = temporary_file(test, "earth.txt", TEXT_EXPORT);
+    let moon = temporary_file(test, "moon.txt", &moon_export(0.5));
+    let table = temporary_file(test, "properties.txt", PROPERTIES);
+    let error = SimState::from_horizons(&[earth, moon], table, 0.01, 1.0)
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(
+        error.contains("Moon (301)") && error.contains("2460311"),
+        "{error}"
+    );
+}
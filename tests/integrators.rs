@@ -0,0 +1,85 @@
+extern crate nalgebra as na;
+use interstellare_simulation::{Body, SimMetaData, SimState, GRAVITATIONAL_CONSTANT};
+use na::Vector3;
+use std::f64::consts::PI;
+
+#[test]
+fn tolerances_must_be_positive() {
+    let meta = SimMetaData::from_string("{\"integrator\": \"dopri\"}").unwrap();
+    assert_eq!(meta.tolerance(), 1e-9);
+    assert_eq!(meta.max_acceleration(), 1e6);
+    for tolerance in ["0", "-1e-9", "NaN"] {
+        assert!(SimMetaData::from_string(&format!("{{\"tolerance\": {tolerance}}}")).is_err());
+    }
+    assert!(SimMetaData::from_string("{\"max_acceleration\": 0}").is_err());
+}
+
+#[test]
+fn reports_cover_the_whole_frame() {
+    let build = || {
+        let mut sim = SimState::new(0.01, 1.0);
+        sim.metadata
+            .update("{\"pacing\": \"fast\", \"integrator\": \"dopri\", \"fixed_dt\": 86400}")
+            .unwrap();
+        sim
+    };
+    let (sender, _receiver) = crossbeam_channel::unbounded();
+    let mut per_step = build();
+    let (mut substeps, mut error) = (0, 0.0_f64);
+    for _ in 0..3 {
+        per_step.fixed_step(sender.clone());
+        let report = per_step.snapshot().report;
+        substeps += report.substeps;
+        error = error.max(report.error);
+    }
+    let mut per_frame = build();
+    for _ in 0..3 {
+        per_frame.fixed_step(sender.clone());
+    }
+    let report = per_frame.snapshot().report;
+    assert!(substeps >= 3 && error > 0.0);
+    //Every accepted sub-step stays within the default tolerance, and so does the largest of them
+    assert!(error <= 1e-9, "{error}");
+    assert_eq!((report.substeps, report.error), (substeps, error));
+    assert_eq!(per_frame.snapshot().report.substeps, 0);
+}
+
+///Distance between start and end of a body of 1 kg flying once around the sun on an orbit with an eccentricity
+///of 0.5 in `steps` steps
+fn kepler_error(integrator: &str, steps: usize) -> f64 {
+    let (mu, a, e) = (GRAVITATIONAL_CONSTANT * 1.98847e30, 1.496e11, 0.5);
+    let periapsis = a * (1.0 - e);
+    let period = 2.0 * PI * (a * a * a / mu).sqrt();
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.bodies = vec![
+        Body::sun(),
+        Body::new(1.0, 1000.0)
+            .set_position(Vector3::new(periapsis, 0.0, 0.0))
+            .set_velocity(Vector3::new(0.0, (mu * (1.0 + e) / periapsis).sqrt(), 0.0)),
+    ];
+    sim.metadata
+        .update(&format!(
+            "{{\"pacing\": \"fast\", \"integrator\": \"{integrator}\", \"fixed_dt\": {}}}",
+            period / steps as f64
+        ))
+        .unwrap();
+    let (sender, _receiver) = crossbeam_channel::unbounded();
+    for _ in 0..steps {
+        sim.fixed_step(sender.clone());
+    }
+    (sim.bodies[1].pos() - sim.bodies[0].pos() - Vector3::new(periapsis, 0.0, 0.0)).norm()
+}
+
+#[test]
+fn integrators_converge_with_their_order() {
+    for (integrator, order) in [("verlet", 2), ("rk4", 4), ("yoshida", 4)] {
+        let coarse = kepler_error(integrator, 2000);
+        let fine = kepler_error(integrator, 4000);
+        //Halving the step divides the error by 2^order
+        let observed = (coarse / fine).log2();
+        assert!(
+            (observed - order as f64).abs() < 0.5,
+            "{integrator}: order {observed}, errors {coarse} and {fine}"
+        );
+    }
+}
@@ -0,0 +1,92 @@
+extern crate nalgebra as na;
+use interstellare_simulation::{Body, SimState, GRAVITATIONAL_CONSTANT};
+use na::Vector3;
+
+const SUN_MASS: f64 = 1.98847e30;
+const EARTH_MASS: f64 = 5.9722e24;
+const DISTANCE: f64 = 1.496e11;
+
+///Sun and earth on circular orbits around their barycentre in the x-y-plane, with the earth on the x-axis
+fn sun_and_earth() -> SimState {
+    let mut sim = SimState::new(0.01, 1.0);
+    let total = SUN_MASS + EARTH_MASS;
+    let speed = (GRAVITATIONAL_CONSTANT * total / DISTANCE).sqrt();
+    sim.bodies = vec![
+        Body::new(SUN_MASS, 1410.0)
+            .set_position(Vector3::new(-DISTANCE * EARTH_MASS / total, 0.0, 0.0))
+            .set_velocity(Vector3::new(0.0, -speed * EARTH_MASS / total, 0.0)),
+        Body::new(EARTH_MASS, 5514.0)
+            .set_position(Vector3::new(DISTANCE * SUN_MASS / total, 0.0, 0.0))
+            .set_velocity(Vector3::new(0.0, speed * SUN_MASS / total, 0.0)),
+    ];
+    sim
+}
+
+#[test]
+fn lagrange_points_corotate_without_force() {
+    let sim = sun_and_earth();
+    let total = SUN_MASS + EARTH_MASS;
+    let sun = Vector3::new(-DISTANCE * EARTH_MASS / total, 0.0, 0.0);
+    let earth = Vector3::new(DISTANCE * SUN_MASS / total, 0.0, 0.0);
+    let rate_squared = GRAVITATIONAL_CONSTANT * total / DISTANCE.powi(3);
+    let lagrange = sim.lagrange_points(0, 1).unwrap();
+    for (n, point) in lagrange.points.iter().enumerate() {
+        let gravity = [(sun, SUN_MASS), (earth, EARTH_MASS)]
+            .iter()
+            .map(|(pos, mass)| {
+                let r = pos - point;
+                GRAVITATIONAL_CONSTANT * mass * r / r.norm().powi(3)
+            })
+            .sum::<Vector3<f64>>();
+        //In the rotating frame gravity exactly provides the centripetal acceleration
+        let centripetal = -rate_squared * point;
+        assert!(
+            (gravity - centripetal).norm() < 1e-9 * centripetal.norm(),
+            "L{} at {point:?}",
+            n + 1
+        );
+    }
+    let [l1, l2, l3, l4, l5] = lagrange.points;
+    assert!(l1.x < earth.x && l2.x > earth.x && l3.x < sun.x);
+    assert!((l1 - earth).norm() > 1.4e9 && (l1 - earth).norm() < 1.6e9);
+    //L4 leads the earth, which moves along y
+    assert!(l4.y > 0.0 && l5.y < 0.0);
+    for point in [l4, l5] {
+        assert!(((point - sun).norm() - DISTANCE).abs() < 1e-6 * DISTANCE);
+        assert!(((point - earth).norm() - DISTANCE).abs() < 1e-6 * DISTANCE);
+    }
+    assert!(lagrange
+        .to_string()
+        .starts_with("{\"primary\": 0, \"secondary\": 1, \"points\": [["));
+}
+
+#[test]
+fn hill_spheres_match_the_known_radii() {
+    let sim = sun_and_earth();
+    let expected = DISTANCE * (EARTH_MASS / (3.0 * SUN_MASS)).cbrt();
+    assert!((sim.hill_radius(1, 0) - expected).abs() < 1e-6 * expected);
+    assert_eq!(
+        sim.lagrange_points(0, 1).unwrap().hill_radius,
+        sim.hill_radius(1, 0)
+    );
+    //Earth and moon of the standard simulation
+    let sim = SimState::new(0.01, 1.0);
+    let moon = sim.hill_radius(4, 3);
+    assert!(moon > 5e7 && moon < 7e7, "Hill radius of the moon {moon}");
+    //Every body gets the Hill sphere around the body it orbits, the sun has none
+    let hill_radii = sim.lagrange_points(3, 4).unwrap().hill_radii;
+    assert_eq!(hill_radii.len(), sim.bodies.len());
+    assert_eq!(hill_radii[0], None);
+    assert_eq!(hill_radii[3], Some(sim.hill_radius(3, 0)));
+    assert_eq!(hill_radii[4], Some(moon));
+}
+
+#[test]
+fn invalid_pairs_are_rejected() {
+    let mut sim = sun_and_earth();
+    assert!(sim.lagrange_points(1, 1).is_err());
+    assert!(sim.lagrange_points(0, 2).is_err());
+    sim.add_body(Body::particle().set_position(Vector3::new(1e9, 0.0, 0.0)));
+    assert!(sim.lagrange_points(0, 2).is_err());
+    assert!(sim.snapshot().lagrange_points(0, 1).is_ok());
+}
@@ -0,0 +1,89 @@
+use interstellare_simulation::{OrbitalElements, GRAVITATIONAL_CONSTANT};
+
+const MU: f64 = GRAVITATIONAL_CONSTANT * 1.98847e30;
+
+fn assert_close(found: &OrbitalElements, expected: &OrbitalElements) {
+    let angles = [
+        (found.inclination, expected.inclination),
+        (
+            found.longitude_of_ascending_node,
+            expected.longitude_of_ascending_node,
+        ),
+        (found.argument_of_periapsis, expected.argument_of_periapsis),
+        (found.true_anomaly, expected.true_anomaly),
+    ];
+    assert!(
+        (found.semi_major_axis - expected.semi_major_axis).abs()
+            < 1e-9 * expected.semi_major_axis.abs()
+            && (found.eccentricity - expected.eccentricity).abs() < 1e-9
+            && angles.iter().all(|(a, b)| (a - b).abs() < 1e-9),
+        "{found} != {expected}"
+    );
+}
+
+#[test]
+fn elements_survive_the_round_trip_through_the_state() {
+    let orbits = [
+        //Eccentric and inclined
+        OrbitalElements {
+            semi_major_axis: 2.7e11,
+            eccentricity: 0.3,
+            inclination: 0.4,
+            longitude_of_ascending_node: 1.2,
+            argument_of_periapsis: 5.0,
+            true_anomaly: 2.5,
+        },
+        //Hyperbolic
+        OrbitalElements {
+            semi_major_axis: -1e11,
+            eccentricity: 1.8,
+            inclination: 2.0,
+            longitude_of_ascending_node: 4.0,
+            argument_of_periapsis: 0.7,
+            true_anomaly: 5.8,
+        },
+        //Circular, so the anomaly is measured from the node
+        OrbitalElements {
+            semi_major_axis: 1.496e11,
+            eccentricity: 0.0,
+            inclination: 0.1,
+            longitude_of_ascending_node: 3.0,
+            argument_of_periapsis: 0.0,
+            true_anomaly: 1.0,
+        },
+        //Equatorial, so the node lies on the x-axis
+        OrbitalElements {
+            semi_major_axis: 5e10,
+            eccentricity: 0.2,
+            inclination: 0.0,
+            longitude_of_ascending_node: 0.0,
+            argument_of_periapsis: 2.0,
+            true_anomaly: 0.5,
+        },
+    ];
+    for elements in orbits {
+        let (r, v) = elements.to_state(MU).unwrap();
+        assert_close(&OrbitalElements::from_state(&r, &v, MU), &elements);
+    }
+}
+
+#[test]
+fn unreachable_states_are_rejected() {
+    let parabolic = OrbitalElements {
+        semi_major_axis: 1e11,
+        eccentricity: 1.0,
+        inclination: 0.0,
+        longitude_of_ascending_node: 0.0,
+        argument_of_periapsis: 0.0,
+        true_anomaly: 0.0,
+    };
+    assert!(parabolic.to_state(MU).is_err());
+    //Beyond the asymptote at acos(-1/e)
+    let hyperbolic = OrbitalElements {
+        semi_major_axis: -1e11,
+        eccentricity: 2.0,
+        true_anomaly: 2.2,
+        ..parabolic
+    };
+    assert!(hyperbolic.to_state(MU).is_err());
+}
@@ -0,0 +1,78 @@
+use interstellare_simulation::{Pacer, SimMetaData, SimState};
+
+fn settings(pacing: &str) -> String {
+    format!(
+        "{{\"pacing\": \"{pacing}\", \"time_scaling\": 100, \"fixed_dt\": 10, \"steps_per_frame\": 3}}"
+    )
+}
+
+fn meta(pacing: &str) -> SimMetaData {
+    SimMetaData::from_string(&settings(pacing)).unwrap()
+}
+
+///Real time between two calls, jittering between 0 and 20 ms
+fn jitter(seed: &mut u64) -> f64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    (*seed >> 11) as f64 / (1u64 << 53) as f64 * 0.02
+}
+
+#[test]
+fn pacers_hand_out_the_due_steps() {
+    //100 simulated seconds per real second make one step of 10 s per 0.1 s
+    let mut pacer = Pacer::new(0.5);
+    let realtime = meta("realtime");
+    let steps: Vec<usize> = [0.125, 0.375, 0.0625, 0.4375]
+        .iter()
+        .map(|&elapsed| pacer.steps_due(&realtime, elapsed))
+        .collect();
+    assert_eq!(steps, vec![1, 4, 0, 5]);
+    //A simulation that cannot keep up drops the missing time
+    assert_eq!(pacer.steps_due(&realtime, 1e3), 1000);
+    assert_eq!(pacer.steps_due(&realtime, 0.0), 0);
+
+    let mut pacer = Pacer::new(0.5);
+    let frame = meta("frame");
+    let steps: Vec<usize> = [0.2, 0.2, 0.2, 0.1, 0.5]
+        .iter()
+        .map(|&elapsed| pacer.steps_due(&frame, elapsed))
+        .collect();
+    assert_eq!(steps, vec![0, 0, 3, 0, 3]);
+
+    let mut pacer = Pacer::new(0.5);
+    assert_eq!(pacer.steps_due(&meta("fast"), 0.0), 1);
+    assert_eq!(pacer.steps_due(&meta("wall"), 1.0), 0);
+    let mut paused = meta("realtime");
+    paused.update("{\"time_scaling\": 0}").unwrap();
+    assert_eq!(pacer.steps_due(&paused, 1.0), 0);
+}
+
+#[test]
+fn trajectories_do_not_depend_on_the_real_time() {
+    let (sender, _receiver) = crossbeam_channel::unbounded();
+    let mut seed = 7;
+    let mut runs = vec![];
+    for pacing in ["realtime", "frame"] {
+        let mut sim = SimState::new(0.01, 1.0);
+        //Only the pacing changes, so the bodies keep attracting each other
+        sim.metadata.update(&settings(pacing)).unwrap();
+        let mut pacer = Pacer::new(0.01);
+        let mut steps = 0;
+        while steps < 60 {
+            let due = pacer.steps_due(&sim.metadata, jitter(&mut seed));
+            for _ in 0..due.min(60 - steps) {
+                sim.fixed_step(sender.clone());
+                steps += 1;
+            }
+        }
+        assert_eq!(sim.time(), 600.0);
+        runs.push(
+            sim.bodies
+                .iter()
+                .map(|b| (b.pos(), b.vel()))
+                .collect::<Vec<_>>(),
+        );
+    }
+    assert_eq!(runs[0], runs[1]);
+}
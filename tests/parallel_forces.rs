@@ -0,0 +1,30 @@
+extern crate nalgebra as na;
+use interstellare_simulation::{Body, SimState};
+use na::Vector3;
+
+#[test]
+fn direct_sum_is_bit_identical_for_any_thread_count() {
+    let bodies: Vec<Body> = (0..500)
+        .map(|i| {
+            let angle = i as f64 * 2.399963;
+            let radius = 1e10 * (1.0 + i as f64);
+            Body::new(1e24 * (1.0 + (i % 7) as f64), 3000.0).set_position(Vector3::new(
+                radius * angle.cos(),
+                radius * angle.sin(),
+                1e9 * (i % 13) as f64,
+            ))
+        })
+        .collect();
+
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.metadata
+        .update("{\"solver\":\"direct\",\"threads\":1}")
+        .unwrap();
+    let single = sim.accelerations(&bodies);
+    for threads in [2, 3, 8] {
+        sim.metadata
+            .update(&format!("{{\"threads\":{threads}}}"))
+            .unwrap();
+        assert_eq!(single, sim.accelerations(&bodies), "{threads} threads");
+    }
+}
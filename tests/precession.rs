@@ -0,0 +1,13 @@
+use interstellare_simulation::mercury_precession;
+
+#[test]
+fn post_newtonian_precession_matches_general_relativity() {
+    let precession = mercury_precession(40.0, 7200.0);
+    let deviation = (precession.measured - precession.predicted).abs() / precession.predicted;
+    assert!(
+        deviation < 0.01,
+        "measured {} arcsec per century, predicted {}",
+        precession.measured,
+        precession.predicted
+    );
+}
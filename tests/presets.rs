@@ -0,0 +1,16 @@
+use interstellare_simulation::{preset, PRESETS};
+
+#[test]
+fn every_preset_builds_a_bound_system() {
+    for (n, p) in PRESETS.iter().enumerate() {
+        assert!(
+            PRESETS[..n].iter().all(|other| other.name != p.name),
+            "{} is registered twice",
+            p.name
+        );
+        let sim = preset(p.name).unwrap().build(0.01, 1.0);
+        assert!(sim.bodies.len() >= 2, "{}", p.name);
+        assert!(sim.diagnostics().total_energy() < 0.0, "{}", p.name);
+    }
+    assert!(preset("unknown").is_err());
+}
@@ -0,0 +1,65 @@
+extern crate nalgebra as na;
+use interstellare_simulation::{Body, InputEvent, SimEvent, SimState};
+use na::Vector3;
+
+#[test]
+fn recentering_moves_the_barycentre_to_rest_in_the_origin() {
+    let mut sim = SimState::new(0.01, 1.0);
+    let before = sim.diagnostics();
+    assert!(before.center_of_mass.norm() > 1e8);
+    sim.recenter();
+    let after = sim.diagnostics();
+    assert!(after.center_of_mass.norm() < 1e-3, "{after}");
+    assert!(
+        after.momentum.norm() < 1e-12 * before.momentum.norm(),
+        "{after}"
+    );
+}
+
+#[test]
+fn the_frame_follows_its_body_when_others_are_removed() {
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.metadata
+        .update("{\"frame\": \"body\", \"frame_body\": 3}")
+        .unwrap();
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    sim.handle_input(InputEvent::Remove(1), None, sender.clone())
+        .unwrap();
+    assert_eq!(sim.metadata.frame_body(), 2);
+    sim.handle_input(InputEvent::Remove(5), None, sender.clone())
+        .unwrap();
+    assert_eq!(sim.metadata.frame_body(), 2);
+    assert_eq!(receiver.try_iter().count(), 2);
+
+    //Once the body itself is gone the client is told that the frame moved to the first body
+    sim.handle_input(InputEvent::Remove(2), None, sender.clone())
+        .unwrap();
+    assert_eq!(sim.metadata.frame_body(), 0);
+    let events: Vec<SimEvent> = receiver.try_iter().collect();
+    assert_eq!(events[1].name(), "reset");
+    assert_eq!(events[1].to_string(), "{\"parameter\": \"frame_body\"}");
+
+    //A preset brings its own metadata
+    sim.metadata.update("{\"frame_body\": 4}").unwrap();
+    sim.load_preset("solar_system", sender).unwrap();
+    assert_eq!(sim.metadata.frame_body(), 0);
+}
+
+#[test]
+fn the_frame_follows_its_body_into_mergers() {
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.bodies = vec![
+        Body::sun(),
+        Body::new(1e20, 3000.0).set_position(Vector3::new(1e10, 0.0, 0.0)),
+        Body::new(1e20, 3000.0).set_position(Vector3::new(1e10, 1e5, 0.0)),
+        Body::new(1e20, 3000.0).set_position(Vector3::new(2e10, 0.0, 0.0)),
+    ];
+    sim.metadata
+        .update("{\"pacing\": \"fast\", \"fixed_dt\": 1, \"frame\": \"body\", \"frame_body\": 2}")
+        .unwrap();
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    sim.fixed_step(sender);
+    assert_eq!(sim.bodies.len(), 3);
+    assert_eq!(receiver.try_iter().next().unwrap().name(), "collision");
+    assert_eq!(sim.metadata.frame_body(), 1);
+}
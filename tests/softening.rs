@@ -0,0 +1,47 @@
+extern crate nalgebra as na;
+use interstellare_simulation::{Body, SimState, GRAVITATIONAL_CONSTANT};
+use na::Vector3;
+
+const MASS: f64 = 1e24;
+const LENGTH: f64 = 1e7;
+
+///The acceleration of a body of 1 kg at `distance` from a body of `MASS`, softened with `softening`
+fn acceleration(softening: &str, distance: f64) -> f64 {
+    let mut sim = SimState::new(0.01, 1.0);
+    sim.metadata
+        .update(&format!(
+            "{{\"softening\": \"{softening}\", \"softening_length\": {LENGTH}}}"
+        ))
+        .unwrap();
+    let bodies = vec![
+        Body::new(MASS, 5000.0),
+        Body::new(1.0, 1000.0).set_position(Vector3::new(distance, 0.0, 0.0)),
+    ];
+    sim.accelerations(&bodies)[1].norm()
+}
+
+fn newton(distance: f64) -> f64 {
+    GRAVITATIONAL_CONSTANT * MASS / (distance * distance)
+}
+
+#[test]
+fn softened_forces_approach_the_analytic_limits() {
+    for softening in ["none", "plummer", "spline"] {
+        let far = 1e4 * LENGTH;
+        assert!(
+            (acceleration(softening, far) - newton(far)).abs() < 1e-6 * newton(far),
+            "{softening}"
+        );
+    }
+    //Plummer: G m r / (r² + ε²)^(3/2), which grows linearly from the centre
+    let plummer = GRAVITATIONAL_CONSTANT * MASS * LENGTH / (2.0 * LENGTH * LENGTH).powf(1.5);
+    assert!((acceleration("plummer", LENGTH) - plummer).abs() < 1e-12 * plummer);
+    let near = 1e-3 * LENGTH;
+    let linear = GRAVITATIONAL_CONSTANT * MASS * near / LENGTH.powi(3);
+    assert!((acceleration("plummer", near) - linear).abs() < 1e-5 * linear);
+    //Spline: Newtonian from the softening length on and 32/3 G m r / ε³ at the centre
+    assert!((acceleration("spline", LENGTH) - newton(LENGTH)).abs() < 1e-12 * newton(LENGTH));
+    let centre = 32.0 / 3.0 * linear;
+    assert!((acceleration("spline", near) - centre).abs() < 1e-4 * centre);
+    assert_eq!(acceleration("none", near), newton(near));
+}
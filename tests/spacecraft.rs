@@ -0,0 +1,199 @@
+extern crate nalgebra as na;
+use interstellare_simulation::{Body, Manoeuvre, SimEvent, SimState, GRAVITATIONAL_CONSTANT};
+use na::Vector3;
+
+const EARTH_MASS: f64 = 5.9722e24;
+const RADIUS: f64 = 7e6;
+
+///A spacecraft of 1 t dry mass with 500 kg propellant on a circular orbit around the earth, flying along y
+fn orbiting(manoeuvre: Manoeuvre) -> SimState {
+    let mut sim = SimState::new(0.01, 1.0);
+    let speed = (GRAVITATIONAL_CONSTANT * EARTH_MASS / RADIUS).sqrt();
+    sim.bodies = vec![
+        Body::new(EARTH_MASS, 5514.0),
+        Body::spacecraft(1000.0, 500.0, 100.0)
+            .set_position(Vector3::new(RADIUS, 0.0, 0.0))
+            .set_velocity(Vector3::new(0.0, speed, 0.0))
+            .with_manoeuvre(manoeuvre),
+    ];
+    sim.metadata
+        .update("{\"pacing\": \"fast\", \"fixed_dt\": 1}")
+        .unwrap();
+    sim
+}
+
+fn run(sim: &mut SimState, steps: usize) -> Vec<SimEvent> {
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    for _ in 0..steps {
+        sim.fixed_step(sender.clone());
+    }
+    receiver.try_iter().collect()
+}
+
+///Semi-major axis after a prograde kick of `delta_v` on the circular orbit
+fn raised_semi_major_axis(delta_v: f64) -> f64 {
+    let mu = GRAVITATIONAL_CONSTANT * EARTH_MASS;
+    let speed = (mu / RADIUS).sqrt() + delta_v;
+    1.0 / (2.0 / RADIUS - speed * speed / mu)
+}
+
+#[test]
+fn impulses_happen_at_their_time() {
+    let mut sim = orbiting(Manoeuvre::Impulse {
+        time: 50.5,
+        delta_v: Vector3::new(0.0, 100.0, 0.0),
+    });
+    run(&mut sim, 50);
+    assert!((sim.orbital_elements(1, 0).semi_major_axis - RADIUS).abs() < 1e3);
+    let events = run(&mut sim, 1);
+    let a = sim.orbital_elements(1, 0).semi_major_axis;
+    assert!((a - raised_semi_major_axis(100.0)).abs() < 0.01 * (a - RADIUS));
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        events[0].to_string(),
+        "{\"index\": 1, \"remaining\": 0, \"propellant\": 500}"
+    );
+    assert!(sim.bodies[1].manoeuvres().is_empty());
+}
+
+#[test]
+fn burns_consume_propellant_by_the_rocket_equation() {
+    let (thrust, isp, duration) = (1000.0, 300.0, 20.0);
+    let mut sim = orbiting(Manoeuvre::Burn {
+        start: 10.0,
+        duration,
+        thrust,
+        isp,
+        direction: Vector3::new(0.0, 2.0, 0.0),
+    });
+    let events = run(&mut sim, 40);
+    let exhaust_velocity = isp * 9.80665;
+    let used = thrust / exhaust_velocity * duration;
+    assert!((sim.bodies[1].propellant() - (500.0 - used)).abs() < 1e-9);
+    let delta_v = exhaust_velocity * (1500.0 / (1500.0 - used)).ln();
+    let a = sim.orbital_elements(1, 0).semi_major_axis;
+    assert!((a - raised_semi_major_axis(delta_v)).abs() < 0.02 * (a - RADIUS));
+    assert_eq!(events.iter().filter(|e| e.name() == "manoeuvre").count(), 1);
+}
+
+#[test]
+fn burns_stop_when_the_tanks_run_dry() {
+    let mut sim = orbiting(Manoeuvre::Burn {
+        start: 0.0,
+        duration: 1000.0,
+        thrust: 10000.0,
+        isp: 300.0,
+        direction: Vector3::new(0.0, 1.0, 0.0),
+    });
+    run(&mut sim, 200);
+    assert_eq!(sim.bodies[1].propellant(), 0.0);
+    let a = sim.orbital_elements(1, 0).semi_major_axis;
+    run(&mut sim, 100);
+    assert!((sim.orbital_elements(1, 0).semi_major_axis - a).abs() < 1e-6 * a);
+}
+
+#[test]
+fn manoeuvres_are_kept_in_order_and_survive_updates() {
+    let burn = Manoeuvre::from_string(
+        "{\"start\": 100, \"duration\": 5, \"thrust\": 20, \"isp\": 220, \"x\": 1, \"y\": 0, \"z\": 0}",
+    )
+    .unwrap();
+    assert_eq!(Manoeuvre::from_string(&burn.to_string()).unwrap(), burn);
+    assert!(Manoeuvre::from_string("{\"start\": 100}").is_err());
+    let impulse = Manoeuvre::Impulse {
+        time: 60.0,
+        delta_v: Vector3::new(1.0, 0.0, 0.0),
+    };
+    let mut sim = orbiting(burn.clone());
+    let (sender, _receiver) = crossbeam_channel::unbounded();
+    sim.handle_input(
+        interstellare_simulation::InputEvent::Manoeuvre(1, impulse.clone()),
+        None,
+        sender.clone(),
+    )
+    .unwrap();
+    assert_eq!(sim.bodies[1].manoeuvres(), &[impulse.clone(), burn][..]);
+    let update = Body::from_string(&format!("{:?}", sim.bodies[1]).replace(" ", "")).unwrap();
+    sim.handle_input(
+        interstellare_simulation::InputEvent::Update(1),
+        Some(update),
+        sender.clone(),
+    )
+    .unwrap();
+    assert_eq!(sim.bodies[1].manoeuvres().len(), 2);
+    assert!(Body::new(1.0, 1.0)
+        .add_manoeuvre(
+            Manoeuvre::from_string("{\"time\": 0, \"dvx\": 1, \"dvy\": 0, \"dvz\": 0}").unwrap()
+        )
+        .is_err());
+    //Manoeuvres for other bodies are rejected instead of stopping the simulation
+    for target in [0, 2] {
+        assert!(sim
+            .handle_input(
+                interstellare_simulation::InputEvent::Manoeuvre(target, impulse.clone()),
+                None,
+                sender.clone(),
+            )
+            .is_err());
+    }
+}
+
+///A spacecraft with a pending burn and impulse touching a body of `mass` kg, colliding according to
+///`collision_policy`
+fn docking(mass: f64, collision_policy: &str) -> SimState {
+    let mut sim = SimState::new(0.01, 1.0);
+    let burn = Manoeuvre::from_string(
+        "{\"start\": 1e6, \"duration\": 10, \"thrust\": 100, \"isp\": 300, \"x\": 1, \"y\": 0, \"z\": 0}",
+    )
+    .unwrap();
+    let impulse = Manoeuvre::Impulse {
+        time: 5.0,
+        delta_v: Vector3::new(0.0, 1000.0, 0.0),
+    };
+    let radius = Body::radius_of(mass, 5514.0);
+    sim.bodies = vec![
+        Body::spacecraft(1000.0, 500.0, 100.0)
+            .with_manoeuvre(burn)
+            .with_manoeuvre(impulse),
+        Body::new(mass, 5514.0).set_position(Vector3::new(radius, 0.0, 0.0)),
+    ];
+    sim.metadata
+        .update(&format!(
+            "{{\"interaction_constant\": 0, \"pacing\": \"fast\", \"fixed_dt\": 1, \"collision_policy\": \"{collision_policy}\", \"fragments\": 4, \"min_fragment_mass\": 1}}"
+        ))
+        .unwrap();
+    sim
+}
+
+#[test]
+fn docked_spacecraft_keep_propellant_and_manoeuvres() {
+    let mut sim = docking(200.0, "merge");
+    run(&mut sim, 1);
+    assert_eq!(sim.bodies.len(), 1);
+    assert!(sim.bodies[0].is_spacecraft());
+    assert!((sim.bodies[0].propellant() - 500.0).abs() < 1e-9);
+    assert_eq!(sim.bodies[0].manoeuvres().len(), 2);
+    //The impulse gives the momentum of the spacecraft alone to the docked pair
+    run(&mut sim, 5);
+    assert!(
+        (sim.bodies[0].vel().y - 1000.0 * 1500.0 / 1700.0).abs() < 1e-9,
+        "{}",
+        sim.bodies[0].vel().y
+    );
+
+    //The wreckage of a spacecraft flies no manoeuvres
+    let mut sim = docking(200.0, "fragment");
+    run(&mut sim, 1);
+    assert_eq!(sim.bodies.len(), 4);
+    assert!(sim.bodies.iter().all(|b| !b.is_spacecraft()));
+}
+
+#[test]
+fn crashed_spacecraft_do_not_steer_planets() {
+    let mut sim = docking(EARTH_MASS, "merge");
+    run(&mut sim, 10);
+    assert_eq!(sim.bodies.len(), 1);
+    assert!(!sim.bodies[0].is_spacecraft());
+    assert!(sim.bodies[0].manoeuvres().is_empty());
+    assert_eq!(sim.bodies[0].vel().y, 0.0);
+}
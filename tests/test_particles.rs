@@ -0,0 +1,58 @@
+extern crate nalgebra as na;
+use interstellare_simulation::{Body, SimState};
+use na::Vector3;
+
+#[test]
+fn particles_feel_gravity_but_do_not_exert_it() {
+    let sim = SimState::new(0.01, 1.0);
+    let massive = sim.bodies.clone();
+    let mut bodies = massive.clone();
+    for i in 0..100 {
+        let angle = i as f64 * 0.0628;
+        bodies.push(
+            Body::particle()
+                .set_position(Vector3::new(angle.cos(), angle.sin(), 0.0) * 4e11)
+                .set_velocity(Vector3::new(-angle.sin(), angle.cos(), 0.0) * 18000.0),
+        );
+    }
+
+    let without = sim.accelerations(&massive);
+    let with = sim.accelerations(&bodies);
+    assert_eq!(without[..], with[..massive.len()]);
+    //A particle accelerates like a massive body of negligible mass at the same place
+    let probe = Body::new(1.0, 1000.0).set_position(Vector3::new(4e11, 0.0, 0.0));
+    let mut probed = massive.clone();
+    probed.push(probe);
+    let expected = sim.accelerations(&probed)[massive.len()];
+    assert!((with[massive.len()] - expected).norm() < 1e-12 * expected.norm());
+}
+
+#[test]
+fn particles_stay_behind_the_massive_bodies() {
+    let mut sim = SimState::new(0.01, 1.0);
+    let n = sim.bodies.len();
+    sim.add_body(Body::particle().set_position(Vector3::new(5e11, 0.0, 0.0)));
+    sim.add_body(Body::new(1e20, 2000.0).set_position(Vector3::new(6e11, 0.0, 0.0)));
+    assert!(!sim.bodies[n].is_particle());
+    assert!(sim.bodies[n + 1].is_particle());
+    let snapshot = sim.snapshot();
+    assert_eq!(snapshot.bodies.len(), n + 1);
+    assert_eq!(snapshot.particles.len(), 1);
+}
+
+#[test]
+fn absorbed_particles_are_not_reported_as_removed_bodies() {
+    let mut sim = SimState::new(0.01, 1.0);
+    let n = sim.bodies.len();
+    sim.add_body(Body::particle().set_position(Vector3::new(1e8, 0.0, 0.0)));
+    sim.add_body(Body::particle().set_position(Vector3::new(4e11, 0.0, 0.0)));
+    sim.metadata
+        .update("{\"pacing\": \"fast\", \"fixed_dt\": 1}")
+        .unwrap();
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    sim.fixed_step(sender);
+    assert_eq!(sim.bodies.len(), n + 1);
+    assert!(sim.bodies[n].is_particle());
+    let events: Vec<_> = receiver.try_iter().collect();
+    assert!(events.is_empty(), "{events:?}");
+}
@@ -0,0 +1,78 @@
+extern crate nalgebra as na;
+use interstellare_simulation::{Body, SimEvent, SimState, GRAVITATIONAL_CONSTANT};
+use na::Vector3;
+
+///Earth with the moon on a circular orbit at `distance`
+fn earth_and_moon(distance: f64, tidal_disruption: bool) -> SimState {
+    let mut sim = SimState::new(0.01, 1.0);
+    let earth = Body::new(5.9722e24, 5515.0);
+    let speed = (GRAVITATIONAL_CONSTANT * 5.9722e24 / distance).sqrt();
+    let moon = Body::new(7.342e22, 3344.0)
+        .set_position(Vector3::new(distance, 0.0, 0.0))
+        .set_velocity(Vector3::new(0.0, speed, 0.0));
+    sim.bodies = vec![earth, moon];
+    sim.metadata
+        .update(&format!(
+            "{{\"pacing\": \"fast\", \"fixed_dt\": 60, \"tidal_disruption\": {tidal_disruption}}}"
+        ))
+        .unwrap();
+    sim
+}
+
+#[test]
+fn moons_inside_the_roche_limit_break_apart_once() {
+    let limit = Body::new(7.342e22, 3344.0).roche_limit(&Body::new(5.9722e24, 5515.0));
+    assert!((limit - 1.836e7).abs() < 1e5, "Roche limit {limit}");
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    let mut sim = earth_and_moon(1.5e7, true);
+    for _ in 0..10 {
+        sim.fixed_step(sender.clone());
+    }
+    let disruptions: Vec<SimEvent> = receiver
+        .try_iter()
+        .filter(|e| e.name() == "disruption")
+        .collect();
+    assert_eq!(disruptions.len(), 1);
+    assert_eq!(
+        disruptions[0].to_string(),
+        "{\"index\": 1, \"primary\": 0, \"first_new\": 2, \"fragments\": 8}"
+    );
+    assert_eq!(sim.bodies.len(), 9);
+}
+
+#[test]
+fn moons_stay_whole_outside_the_roche_limit_or_without_the_model() {
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    for mut sim in [earth_and_moon(2.5e7, true), earth_and_moon(1.5e7, false)] {
+        for _ in 0..10 {
+            sim.fixed_step(sender.clone());
+        }
+        assert_eq!(sim.bodies.len(), 2);
+    }
+    assert_eq!(receiver.try_iter().count(), 0);
+}
+
+///Mass and momentum of all bodies
+fn mass_and_momentum(sim: &SimState) -> (f64, Vector3<f64>) {
+    sim.bodies
+        .iter()
+        .fold((0.0, Vector3::zeros()), |(m, p), body| {
+            (m + body.mass(), p + body.vel() * body.mass())
+        })
+}
+
+#[test]
+fn disruptions_conserve_mass_and_momentum() {
+    let (sender, _receiver) = crossbeam_channel::unbounded();
+    let mut sim = earth_and_moon(1.5e7, true);
+    let (mass, momentum) = mass_and_momentum(&sim);
+    sim.fixed_step(sender);
+    assert_eq!(sim.bodies.len(), 9);
+    let (after_mass, after_momentum) = mass_and_momentum(&sim);
+    assert!((after_mass - mass).abs() < 1e-12 * mass);
+    //The moon alone carries a momentum of about 4e26 kg m/s
+    assert!(
+        (after_momentum - momentum).norm() < 1e16,
+        "{after_momentum} {momentum}"
+    );
+}
@@ -83,12 +83,45 @@ createBody.addEventListener("click", () => {
   clickBlocker.append(detailMenu, confirm);
   body.append(clickBlocker);
 });
+/**
+ * Dropdown to replace the simulation by one of the presets of the backend
+ */
+const presetSelect = createElement(
+  { tag: "select" },
+  "presetSelect"
+) as HTMLSelectElement;
+fetch("/presets")
+  .then((response) => response.json())
+  .then((presets: { name: string; description: string }[]) => {
+    presets.forEach((preset) => {
+      const option = document.createElement("option");
+      option.value = preset.name;
+      option.innerText = preset.name;
+      option.title = preset.description;
+      presetSelect.append(option);
+    });
+  });
+presetSelect.addEventListener("change", () => {
+  fetch("/presets", {
+    method: "POST",
+    headers: { "Content-Type": "application/json" },
+    body: JSON.stringify({ name: presetSelect.value }),
+  });
+});
 /**
  * Message to tell the user to pause if modifications are wanted
  */
 const help = createElement({}, "text", "help");
 help.innerText = "Pause to modify the simulation";
-settingsMenu.append(playButton, gravity, time, resetCam, createBody, help);
+settingsMenu.append(
+  playButton,
+  gravity,
+  time,
+  resetCam,
+  createBody,
+  presetSelect,
+  help
+);
 
 /**
  * Menu to show the informations of the selected body
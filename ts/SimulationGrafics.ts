@@ -38,6 +38,9 @@ let finishedPaths: number[] = [];
 
 let bodies: Body[] = [];
 
+/**Positions of the test particles as flat array [x0, y0, z0, x1, ...]*/
+let particles: number[] = [];
+
 /**Indicates the necessity to rescale the view*/
 let updateScaleFlag = true;
 
@@ -214,9 +217,9 @@ const getBodyInfo = (body_index: number) => {
 };
 
 /**
- * Default list of bodies in the simulation
+ * Names of the bodies of the standard simulation, the solar system
  */
-let bodyNames = [
+const SOLARSYSTEMNAMES = [
   "Sol",
   "Mercury",
   "Venus",
@@ -228,6 +231,10 @@ let bodyNames = [
   "Uranus",
   "Neptun",
 ];
+/**
+ * Default list of bodies in the simulation
+ */
+let bodyNames = [...SOLARSYSTEMNAMES];
 /**
  * Used to change the Name of the body given by index. If index === -1 a new entry will be made
  * @param name The name to be included in 'bodyNames'
@@ -239,9 +246,9 @@ const setBodyName = (name: string, index = -1) => {
   else bodyNames[index] = name;
 };
 /**
- * Default colors of the default bodies in the simulation
+ * Colors of the bodies of the standard simulation, the solar system
  */
-let bodyColors = [
+const SOLARSYSTEMCOLORS = [
   "#E2F516", //sun
   "#786D5F", //mercury
   "#3B2F2F", //venus
@@ -253,6 +260,10 @@ let bodyColors = [
   "#2B65EC", //uranus
   "#56A5EC", //neptun
 ];
+/**
+ * Default colors of the default bodies in the simulation
+ */
+let bodyColors = [...SOLARSYSTEMCOLORS];
 /**
  * Used to change the Color of the body given by index. If index === -1 a new entry will be made
  * @param color The color to be included in 'bodyColors'
@@ -297,6 +308,21 @@ const drawBodies = () => {
   });
 };
 
+/**
+ * Draws the test particles as single pixels.
+ */
+const drawParticles = () => {
+  context.fillStyle = "gray";
+  for (let i = 0; i + 2 < particles.length; i += 3) {
+    const [x, y] = pointToScreenCoords({
+      x: particles[i],
+      y: particles[i + 1],
+      z: particles[i + 2],
+    });
+    context.fillRect(x, y, 1, 1);
+  }
+};
+
 /**
  * Draw the Paths the bodies have taken.
  */
@@ -412,11 +438,53 @@ const startSimulation = () => {
     console.log(d);
     EventBUS.fireEvent("removeBody", { body_index: d.index });
   });
+  eventSource.addEventListener("collision", (e) => {
+    const d = JSON.parse(e.data);
+    console.log(d);
+    EventBUS.fireEvent("removeBody", { body_index: d.removed });
+  });
+  eventSource.addEventListener("bounce", (e) => {
+    const d = JSON.parse(e.data);
+    console.log(d);
+    restartPaths(d.first, d.second);
+  });
+  eventSource.addEventListener("fragmentation", (e) => {
+    const d = JSON.parse(e.data);
+    console.log(d);
+    restartPaths(d.first, d.second);
+    insertFragments(d.first, d.first_new, d.fragments - 2);
+  });
+  eventSource.addEventListener("disruption", (e) => {
+    const d = JSON.parse(e.data);
+    console.log(d);
+    restartPaths(d.index);
+    insertFragments(d.index, d.first_new, d.fragments - 1);
+  });
+  eventSource.addEventListener("reset", (e) => {
+    const d = JSON.parse(e.data);
+    console.warn(`The body of "${d.parameter}" is gone, it now refers to body 0`);
+  });
+  eventSource.addEventListener("loaded", (e) => {
+    const d = JSON.parse(e.data);
+    paths = {};
+    finishedPaths = [];
+    bodies = [];
+    particles = [];
+    //Only the solar system has known bodies, the bodies of other presets get random names and colors
+    const solarSystem = d.name === "solar_system";
+    bodyNames = solarSystem ? [...SOLARSYSTEMNAMES] : [];
+    bodyColors = solarSystem ? [...SOLARSYSTEMCOLORS] : [];
+    moonIndices = solarSystem ? [4] : [];
+    while (bodyNames.length < d.bodies) bodyNames.push(randomName());
+    while (bodyColors.length < d.bodies) bodyColors.push(randomColor());
+    EventBUS.fireEvent("resetCam", {});
+  });
   eventSource.addEventListener("simulation", (e) => {
     const d = JSON.parse(e.data);
     EventBUS.fireEvent("metadata", d.metadata);
     const oldPos = [...bodies];
     bodies = d.simstate;
+    particles = d.particles ?? [];
     bodies.forEach((b, i) => {
       if (finishedPaths.includes(i)) return;
       if (!(i in paths)) paths[i] = [];
@@ -450,6 +518,7 @@ const startSimulation = () => {
     keyboardHandler,
     () => context.clearRect(0, 0, c.width, c.height),
     drawPaths,
+    drawParticles,
     drawBodies
   );
   EventBUS.registerEventListener({ eventType: "dbclickedBody" }, (event) => {
@@ -483,6 +552,43 @@ const startSimulation = () => {
   });
 };
 
+/**
+ * Starts new paths for the bodies given by their indices, as their orbits changed abruptly.
+ * @param indices The indices of the bodies
+ */
+const restartPaths = (...indices: number[]) => {
+  indices.forEach((i) => {
+    if (i in paths) paths[i] = [];
+  });
+  finishedPaths = finishedPaths.filter((i) => !indices.includes(i));
+};
+
+/**
+ * Makes room for new fragments of a body, which take over its name and color. The bodies behind them
+ * move back by their count.
+ * @param parent The index of the broken body
+ * @param firstNew The index of the first new fragment
+ * @param count The number of new fragments
+ */
+const insertFragments = (parent: number, firstNew: number, count: number) => {
+  while (bodyNames.length < firstNew) bodyNames.push(randomName());
+  while (bodyColors.length < firstNew) bodyColors.push(randomColor());
+  const name = bodyNames[parent] ?? randomName();
+  const color = bodyColors[parent] ?? randomColor();
+  const fragments = [...Array(count).keys()];
+  bodyNames.splice(firstNew, 0, ...fragments.map((n) => `${name} ${n + 1}`));
+  bodyColors.splice(firstNew, 0, ...fragments.map(() => color));
+  paths = Object.entries(paths).reduce((newObj, [i, path]) => {
+    const index = Number(i);
+    (newObj as { [key: number]: Position3D[] })[
+      index >= firstNew ? index + count : index
+    ] = path;
+    return newObj;
+  }, {});
+  finishedPaths = finishedPaths.map((i) => (i >= firstNew ? i + count : i));
+  moonIndices = moonIndices.map((i) => (i >= firstNew ? i + count : i));
+};
+
 /**
  * Toggles the moon status of a body given by its index.
  * @param index The index of the body